@@ -0,0 +1,108 @@
+// Compare against a run with `--features parallel` to see the prover scale across cores:
+//   cargo bench --bench sumcheck
+//   cargo bench --bench sumcheck --features parallel
+// On one core the parallel build only adds rayon's overhead. Setting RAYON_NUM_THREADS on a
+// machine with several cores shows how the speedup grows with the number of threads.
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use thaler::{
+    fields::{random_vec, Fr},
+    ip::{Driver, Status},
+    mle::{
+        first_variable_evals, fix_first_variable, random_evals, stream_eval, DenseMLE, ProductMLE,
+        SparseMLE,
+    },
+    sumcheck::{SumCheck, SumCheckPolynomial},
+    virtual_polynomial::VirtualPolynomial,
+};
+
+const NUM_VARS: [usize; 3] = [12, 16, 20];
+
+fn bench_stream_eval(c: &mut Criterion) {
+    let mut group = c.benchmark_group("stream_eval");
+    group.sample_size(10);
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    // O(v * 2^v), so kept smaller than the prover benchmarks
+    for v in [10, 14, 16] {
+        let evals = random_evals::<Fr, _>(v, &mut rng);
+        let r = random_vec::<Fr, _>(v, &mut rng);
+        group.bench_with_input(BenchmarkId::from_parameter(v), &v, |b, &v| {
+            b.iter(|| stream_eval(&r, &evals, v))
+        });
+    }
+    group.finish();
+}
+
+fn bench_prover_round(c: &mut Criterion) {
+    let mut group = c.benchmark_group("prover_round");
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    for v in NUM_VARS {
+        let evals = random_evals::<Fr, _>(v, &mut rng);
+        let r = random_vec::<Fr, _>(1, &mut rng)[0];
+        group.bench_with_input(BenchmarkId::from_parameter(v), &evals, |b, evals| {
+            b.iter(|| (first_variable_evals(evals, 2), fix_first_variable(evals, r)))
+        });
+    }
+    group.finish();
+}
+
+// A round of the provers for products of MLEs, sums of such products, and sparse MLEs
+fn bench_structured_prover_round(c: &mut Criterion) {
+    let mut group = c.benchmark_group("structured_prover_round");
+    group.sample_size(10);
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    for v in NUM_VARS {
+        let r = random_vec::<Fr, _>(1, &mut rng)[0];
+        let tables: Vec<Vec<Fr>> = (0..3).map(|_| random_evals(v, &mut rng)).collect();
+        let product = ProductMLE::product(tables.clone());
+        group.bench_with_input(BenchmarkId::new("product", v), &product, |b, poly| {
+            b.iter(|| (poly.first_variable_evals(4), poly.fix_first_variable(r)))
+        });
+
+        let [f, g, h] =
+            [0, 1, 2].map(|i| VirtualPolynomial::from(DenseMLE::new(tables[i].clone())));
+        let virtual_poly = f.clone() * g.clone() * h + f * Fr::from(2) - g;
+        group.bench_with_input(BenchmarkId::new("virtual", v), &virtual_poly, |b, poly| {
+            b.iter(|| (poly.first_variable_evals(4), poly.fix_first_variable(r)))
+        });
+
+        // One entry in 16 is nonzero
+        let sparse = SparseMLE::new(
+            v,
+            (0..1 << v)
+                .step_by(16)
+                .zip(random_vec::<Fr, _>(1 << (v - 4), &mut rng)),
+        );
+        group.bench_with_input(BenchmarkId::new("sparse", v), &sparse, |b, poly| {
+            b.iter(|| (poly.first_variable_evals(2), poly.fix_first_variable(r)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_sumcheck(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sumcheck");
+    group.sample_size(10);
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    for v in NUM_VARS {
+        let evals = random_evals::<Fr, _>(v, &mut rng);
+        group.bench_with_input(BenchmarkId::from_parameter(v), &evals, |b, evals| {
+            b.iter(|| {
+                let mut ip =
+                    Driver::<SumCheck<Fr>>::with_seed(evals.clone(), thaler::adversary::Honest, 0);
+                assert_eq!(ip.run(), Status::Accepted);
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_stream_eval,
+    bench_prover_round,
+    bench_structured_prover_round,
+    bench_sumcheck
+);
+criterion_main!(benches);
@@ -0,0 +1,266 @@
+use crate::{ip::*, polynomials::eval_poly, transcript::Transcript};
+use ark_ff::{FftField, Field, One};
+use ark_std::UniformRand;
+use rand::Rng;
+
+// A cheating strategy for the prover. The driver hands every message the honest prover would
+// have sent to the adversary, along with everything sent so far, and sends whatever it returns.
+pub trait Adversary<I: IP> {
+    fn intercept<R: Rng>(
+        &mut self,
+        honest_message: I::ProverMessage,
+        transcript: &Transcript<I::ProverMessage, I::VerifierMessage>,
+        rng: &mut R,
+    ) -> I::ProverMessage;
+}
+
+// Forwards the honest prover's messages untouched
+#[derive(Debug, Clone, Default)]
+pub struct Honest;
+
+impl<I: IP> Adversary<I> for Honest {
+    fn intercept<R: Rng>(
+        &mut self,
+        honest_message: I::ProverMessage,
+        _transcript: &Transcript<I::ProverMessage, I::VerifierMessage>,
+        _rng: &mut R,
+    ) -> I::ProverMessage {
+        honest_message
+    }
+}
+
+// Prover messages made up of field elements, which the generic strategies below tamper with.
+pub trait FieldMessage {
+    type Field: Field;
+
+    fn entries_mut(&mut self) -> impl Iterator<Item = &mut Self::Field>;
+}
+
+impl<F: Field> FieldMessage for Vec<F> {
+    type Field = F;
+
+    fn entries_mut(&mut self) -> impl Iterator<Item = &mut F> {
+        self.iter_mut()
+    }
+}
+
+fn targets_round<P, V>(round: Option<usize>, transcript: &Transcript<P, V>) -> bool {
+    round.is_none_or(|round| round == transcript.current_round())
+}
+
+// Adds one to a single entry of the prover's message, in one round or (if `round` is None) in
+// every round.
+#[derive(Debug, Clone)]
+pub struct CorruptEntry {
+    pub index: usize,
+    pub round: Option<usize>,
+}
+
+impl CorruptEntry {
+    pub fn every_round(index: usize) -> Self {
+        Self { index, round: None }
+    }
+
+    pub fn in_round(round: usize, index: usize) -> Self {
+        Self {
+            index,
+            round: Some(round),
+        }
+    }
+}
+
+impl<I: IP> Adversary<I> for CorruptEntry
+where
+    I::ProverMessage: FieldMessage,
+{
+    fn intercept<R: Rng>(
+        &mut self,
+        mut honest_message: I::ProverMessage,
+        transcript: &Transcript<I::ProverMessage, I::VerifierMessage>,
+        _rng: &mut R,
+    ) -> I::ProverMessage {
+        if targets_round(self.round, transcript) {
+            if let Some(entry) = honest_message.entries_mut().nth(self.index) {
+                *entry += <I::ProverMessage as FieldMessage>::Field::one();
+            }
+        }
+        honest_message
+    }
+}
+
+// Replaces every entry of the prover's message with a uniformly random field element, in one
+// round or (if `round` is None) in every round.
+#[derive(Debug, Clone, Default)]
+pub struct RandomMessage {
+    pub round: Option<usize>,
+}
+
+impl<I: IP> Adversary<I> for RandomMessage
+where
+    I::ProverMessage: FieldMessage,
+{
+    fn intercept<R: Rng>(
+        &mut self,
+        mut honest_message: I::ProverMessage,
+        transcript: &Transcript<I::ProverMessage, I::VerifierMessage>,
+        rng: &mut R,
+    ) -> I::ProverMessage {
+        if targets_round(self.round, transcript) {
+            for entry in honest_message.entries_mut() {
+                *entry = <I::ProverMessage as FieldMessage>::Field::rand(rng);
+            }
+        }
+        honest_message
+    }
+}
+
+// The strategies below are for protocols like sumcheck, where the prover sends a univariate
+// polynomial g_i as evaluations at 0, 1, 2, ... and the verifier replies with a challenge r_i,
+// checking that g_i(0) + g_i(1) = g_{i-1}(r_{i-1}).
+
+// Adds c * X to the honest polynomial so that g(0) + g(1) equals the claim left by the previous
+// message the adversary sent, rather than the honest one.
+fn stay_consistent<F: FftField>(
+    mut honest_message: Vec<F>,
+    transcript: &Transcript<Vec<F>, F>,
+) -> Vec<F> {
+    let (Some(previous), Some(r)) = (
+        transcript.prover_messages().last(),
+        transcript.verifier_messages().last(),
+    ) else {
+        return honest_message;
+    };
+    let c = eval_poly(previous, *r)
+        - (eval_poly(&honest_message, F::zero()) + eval_poly(&honest_message, F::one()));
+    for (x, eval) in honest_message.iter_mut().enumerate() {
+        *eval += c * F::from(x as u64);
+    }
+    honest_message
+}
+
+// Lies about g_k in round k in a way that still passes that round's check, by adding 2X - 1
+// (which sums to zero over {0, 1}), then keeps every later message consistent with the lie. Only
+// the verifier's final check can catch it.
+#[derive(Debug, Clone)]
+pub struct LieInRound {
+    pub round: usize,
+}
+
+impl<I, F> Adversary<I> for LieInRound
+where
+    F: FftField,
+    I: IP<ProverMessage = Vec<F>, VerifierMessage = F>,
+{
+    fn intercept<R: Rng>(
+        &mut self,
+        honest_message: Vec<F>,
+        transcript: &Transcript<Vec<F>, F>,
+        _rng: &mut R,
+    ) -> Vec<F> {
+        let round = transcript.current_round();
+        if round < self.round {
+            return honest_message;
+        }
+        let mut message = stay_consistent(honest_message, transcript);
+        if round == self.round {
+            for (x, eval) in message.iter_mut().enumerate() {
+                *eval += F::from(2 * x as u64) - F::one();
+            }
+        }
+        message
+    }
+}
+
+// Sends a polynomial of one degree more than the protocol allows in round k, by adding
+// X(X - 1)...(X - d) to the honest g_k of degree d, which leaves its evaluations at 0, ..., d
+// unchanged and appends one more at d + 1. Keeps every later message consistent with it.
+#[derive(Debug, Clone)]
+pub struct ExceedDegree {
+    pub round: usize,
+}
+
+impl<I, F> Adversary<I> for ExceedDegree
+where
+    F: FftField,
+    I: IP<ProverMessage = Vec<F>, VerifierMessage = F>,
+{
+    fn intercept<R: Rng>(
+        &mut self,
+        honest_message: Vec<F>,
+        transcript: &Transcript<Vec<F>, F>,
+        _rng: &mut R,
+    ) -> Vec<F> {
+        let round = transcript.current_round();
+        if round < self.round {
+            return honest_message;
+        }
+        let mut message = stay_consistent(honest_message, transcript);
+        if round == self.round {
+            let n = message.len() as u64;
+            let vanishing: F = (1..=n).map(F::from).product();
+            message.push(eval_poly(&message, F::from(n)) + vanishing);
+        }
+        message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fields::Fr, mle::random_evals, sumcheck::SumCheck};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_corrupt_entry() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let evals = random_evals::<Fr, _>(3, &mut rng);
+        let mut ip = Driver::<SumCheck<Fr>, _>::with_seed(evals, CorruptEntry::in_round(1, 0), 0);
+        ip.log_rejections = false;
+        assert_eq!(ip.run(), Status::Rejected);
+        // Caught as soon as the corrupted message is checked
+        assert_eq!(ip.transcript().entries.len(), 4);
+    }
+
+    #[test]
+    fn test_random_message() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let evals = random_evals::<Fr, _>(3, &mut rng);
+        let mut ip = Driver::<SumCheck<Fr>, _>::with_seed(evals, RandomMessage::default(), 0);
+        ip.log_rejections = false;
+        assert_eq!(ip.run(), Status::Rejected);
+        // A random g_1 of the right size, which does not sum to the claim
+        let first = ip.transcript().prover_messages().next().unwrap();
+        assert_eq!(first.len(), 2);
+        assert_eq!(ip.transcript().entries.len(), 2);
+    }
+
+    #[test]
+    fn test_lie_in_round_passes_intermediate_checks() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let v = 4;
+        let evals = random_evals::<Fr, _>(v, &mut rng);
+        let mut ip = Driver::<SumCheck<Fr>, _>::with_seed(evals, LieInRound { round: 1 }, 0);
+        ip.log_rejections = false;
+
+        // The lie is never caught by a consistency check, so every round is played out, and the
+        // final query catches it
+        assert_eq!(ip.run(), Status::Rejected);
+        assert_eq!(ip.transcript().entries.len(), 2 * v);
+    }
+
+    #[test]
+    fn test_exceed_degree() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let v = 3;
+        let evals = random_evals::<Fr, _>(v, &mut rng);
+        let mut ip = Driver::<SumCheck<Fr>, _>::with_seed(evals, ExceedDegree { round: 1 }, 0);
+        ip.log_rejections = false;
+
+        // Caught by the degree check even though g(0) + g(1) is consistent
+        assert_eq!(ip.run(), Status::Rejected);
+        let second = ip.transcript().prover_messages().nth(1).unwrap();
+        assert_eq!(second.len(), 3);
+        assert_eq!(ip.transcript().entries.len(), 4);
+    }
+}
@@ -0,0 +1,226 @@
+use crate::{
+    adversary::{Adversary, Honest},
+    ip::*,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
+
+const DOMAIN_SEPARATOR: &[u8] = b"thaler-fiat-shamir-v1";
+
+/// Hash chain over everything the verifier has seen so far. Verifier coins are derived by seeding
+/// a ChaCha rng from the current state, so a challenge depends on every message absorbed before it.
+#[derive(Debug, Clone)]
+pub struct Sponge {
+    state: [u8; 32],
+}
+
+impl Sponge {
+    pub fn new(label: &[u8]) -> Self {
+        Self {
+            state: Sha256::new().chain_update(label).finalize().into(),
+        }
+    }
+
+    pub fn absorb<T: CanonicalSerialize>(&mut self, item: &T) {
+        let mut bytes = vec![];
+        item.serialize_compressed(&mut bytes)
+            .expect("serializing into a Vec cannot fail");
+        self.state = Sha256::new()
+            .chain_update(self.state)
+            .chain_update(b"absorb")
+            .chain_update(bytes)
+            .finalize()
+            .into();
+    }
+
+    pub fn squeeze_seed(&mut self) -> [u8; 32] {
+        let seed: [u8; 32] = Sha256::new()
+            .chain_update(self.state)
+            .chain_update(b"squeeze")
+            .finalize()
+            .into();
+        // Ratchet the state so that consecutive squeezes give independent coins
+        self.state = Sha256::new().chain_update(seed).finalize().into();
+        seed
+    }
+
+    pub fn squeeze_rng(&mut self) -> ChaCha20Rng {
+        ChaCha20Rng::from_seed(self.squeeze_seed())
+    }
+}
+
+/// Non-interactive proof: the prover's side of the transcript. The verifier's messages are not
+/// included since they are recomputed from the sponge during verification.
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Proof<M: CanonicalSerialize + CanonicalDeserialize> {
+    pub prover_messages: Vec<M>,
+}
+
+/// Fiat-Shamir transform of a public-coin IP. The verifier's coins in each round are derived by
+/// hashing the verifier's input and all prior messages instead of being sampled interactively.
+pub trait FiatShamir: IP
+where
+    Self::VerifierInput: CanonicalSerialize,
+    Self::ProverMessage: CanonicalSerialize + CanonicalDeserialize,
+    Self::VerifierMessage: CanonicalSerialize,
+{
+    fn prove(input: Self::Input) -> Proof<Self::ProverMessage> {
+        // The honest prover is deterministic, so the seed is never used
+        Self::prove_with_adversary(input, Honest, 0)
+    }
+
+    // `seed` seeds the adversary's coins
+    fn prove_with_adversary<A: Adversary<Self>>(
+        input: Self::Input,
+        adversary: A,
+        seed: u64,
+    ) -> Proof<Self::ProverMessage> {
+        let (prover_input, verifier_input) = Self::split_input(input);
+        let mut sponge = Sponge::new(DOMAIN_SEPARATOR);
+        sponge.absorb(&verifier_input);
+
+        // The prover runs its own copy of the verifier to derive the challenges
+        let mut ip = Driver::from_parts(
+            Self::Prover::initialize(prover_input),
+            Self::Verifier::initialize(verifier_input),
+            adversary,
+            seed,
+        );
+        while ip.get_status() == Status::Running {
+            sponge.absorb(ip.prover_turn());
+            let seed = sponge.squeeze_seed();
+            sponge.absorb(ip.verifier_turn(seed));
+        }
+
+        Proof {
+            prover_messages: ip.transcript().prover_messages().cloned().collect(),
+        }
+    }
+
+    /// Replays the verifier against the messages in `proof`, without access to a prover. Returns
+    /// `Status::Rejected` if the proof runs out before the verifier decides, or has messages left
+    /// over once it has.
+    fn verify(proof: &Proof<Self::ProverMessage>, input: Self::VerifierInput) -> Status {
+        let mut sponge = Sponge::new(DOMAIN_SEPARATOR);
+        sponge.absorb(&input);
+
+        let mut verifier = Self::Verifier::initialize(input);
+        let mut prover_messages = proof.prover_messages.iter();
+        while verifier.get_status() == Status::Running {
+            let Some(prover_message) = prover_messages.next() else {
+                return Status::Rejected;
+            };
+            sponge.absorb(prover_message);
+            verifier.receive_prover_message(prover_message.clone());
+
+            let verifier_message = verifier.run_verifier_logic(&mut sponge.squeeze_rng());
+            sponge.absorb(&verifier_message);
+        }
+
+        if prover_messages.next().is_some() {
+            return Status::Rejected;
+        }
+        verifier.get_status()
+    }
+}
+
+impl<I> FiatShamir for I
+where
+    I: IP,
+    I::VerifierInput: CanonicalSerialize,
+    I::ProverMessage: CanonicalSerialize + CanonicalDeserialize,
+    I::VerifierMessage: CanonicalSerialize,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        adversary::CorruptEntry,
+        fields::Fr,
+        freivalds::{MatMulIP, Matrix, F},
+        mle::random_evals,
+        sumcheck::SumCheck,
+    };
+    use ark_ff::One;
+    use ndarray::Array2;
+
+    #[test]
+    fn test_sumcheck_fiat_shamir() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let evals = random_evals::<Fr, _>(4, &mut rng);
+        let (_, statement) = SumCheck::<Fr>::split_input(evals.clone());
+
+        let proof = SumCheck::<Fr>::prove(evals.clone());
+        assert_eq!(proof.prover_messages.len(), 4);
+        assert_eq!(
+            SumCheck::<Fr>::verify(&proof, statement.clone()),
+            Status::Accepted
+        );
+
+        // Proofs survive a serialization round trip
+        let mut bytes = vec![];
+        proof.serialize_compressed(&mut bytes).unwrap();
+        let decoded = Proof::<Vec<Fr>>::deserialize_compressed(bytes.as_slice()).unwrap();
+        assert_eq!(
+            SumCheck::<Fr>::verify(&decoded, statement.clone()),
+            Status::Accepted
+        );
+
+        // Truncated proofs are rejected
+        let mut truncated = proof.clone();
+        truncated.prover_messages.pop();
+        assert_eq!(
+            SumCheck::<Fr>::verify(&truncated, statement.clone()),
+            Status::Rejected
+        );
+
+        let malicious_proof =
+            SumCheck::<Fr>::prove_with_adversary(evals, CorruptEntry::every_round(0), 0);
+        assert_eq!(
+            SumCheck::<Fr>::verify(&malicious_proof, statement.clone()),
+            Status::Rejected
+        );
+    }
+
+    #[test]
+    fn test_freivalds_fiat_shamir() {
+        let n = 20;
+        let a = Matrix(Array2::<F>::from_elem((n, n), F::one()));
+        let b = Matrix(Array2::<F>::from_elem((n, n), F::one() + F::one()));
+
+        let proof = MatMulIP::prove((a.clone(), b.clone()));
+        assert_eq!(
+            MatMulIP::verify(&proof, (a.clone(), b.clone())),
+            Status::Accepted
+        );
+
+        // A proof for one statement does not verify for another
+        let mut other_b = b.clone();
+        other_b.0[(0, 0)] += F::one();
+        assert_eq!(MatMulIP::verify(&proof, (a, other_b)), Status::Rejected);
+    }
+
+    #[test]
+    fn test_sponge_determinism() {
+        use rand::RngCore;
+
+        let mut sponge_a = Sponge::new(b"test");
+        let mut sponge_b = Sponge::new(b"test");
+        sponge_a.absorb(&Fr::from(3));
+        sponge_b.absorb(&Fr::from(3));
+        assert_eq!(
+            sponge_a.squeeze_rng().next_u64(),
+            sponge_b.squeeze_rng().next_u64()
+        );
+
+        // Consecutive squeezes produce different coins
+        assert_ne!(
+            sponge_a.squeeze_rng().next_u64(),
+            sponge_a.squeeze_rng().next_u64()
+        );
+    }
+}
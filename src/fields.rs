@@ -1,7 +1,8 @@
-use std::usize;
+// `#[derive(MontConfig)]` expands to impls inside a const block
+#![allow(non_local_definitions)]
 
 use ark_ff::{Field, Fp64, MontBackend, MontConfig};
-use rand::thread_rng;
+use rand::Rng;
 
 #[derive(MontConfig)]
 #[modulus = "5"]
@@ -10,13 +11,12 @@ pub struct FrConfig;
 
 pub type Fr = Fp64<MontBackend<FrConfig, 1>>;
 
-pub fn random_elem<F: Field>() -> F {
-    let mut rng = thread_rng();
-    F::rand(&mut rng)
+pub fn random_elem<F: Field, R: Rng>(rng: &mut R) -> F {
+    F::rand(rng)
 }
 
-pub fn random_vec<F: Field>(v: usize) -> Vec<F> {
-    (0..v).map(|_| random_elem()).collect()
+pub fn random_vec<F: Field, R: Rng>(v: usize, rng: &mut R) -> Vec<F> {
+    (0..v).map(|_| random_elem(rng)).collect()
 }
 
 pub fn bool_to_field<F: Field>(b: bool) -> F {
@@ -30,3 +30,22 @@ pub fn bool_to_field<F: Field>(b: bool) -> F {
 pub fn evals_sum<F: Field>(evals: &[F]) -> F {
     evals.iter().fold(F::zero(), |acc, &x| acc + x)
 }
+
+// |F| = p^k, as a float since it is only used for probability estimates
+pub fn field_size<F: Field>() -> f64 {
+    let p = F::characteristic()
+        .iter()
+        .rev()
+        .fold(0f64, |acc, &limb| acc * 2f64.powi(64) + limb as f64);
+    p.powi(F::extension_degree() as i32)
+}
+
+// Whether 0, 1, ..., n - 1 are n distinct elements of F, which holds exactly when n is at most the
+// characteristic
+pub fn distinct_points<F: Field>(n: usize) -> bool {
+    let p = F::characteristic();
+    let (low, high) = p.split_first().unwrap_or((&0, &[]));
+    (*low == 0 && high.iter().all(|limb| *limb == 0))
+        || high.iter().any(|limb| *limb != 0)
+        || n as u64 <= *low
+}
@@ -1,62 +1,171 @@
-use crate::ip::*;
+// `#[derive(MontConfig)]` expands to impls inside a const block
+#![allow(non_local_definitions)]
+
+use crate::{adversary::FieldMessage, fields::field_size, ip::*, soundness::SoundnessBound};
 use ark_ff::{Fp64, MontBackend, MontConfig, One};
-use ark_std::UniformRand;
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Valid, Validate,
+};
+use ark_std::{
+    io::{Read, Write},
+    UniformRand,
+};
 use ndarray::{Array1, Array2};
+use rand::Rng;
 
 #[derive(MontConfig)]
 #[modulus = "17"]
-#[generator = "2"]
-struct FrConfig;
+#[generator = "3"]
+pub struct FrConfig;
+
+pub type F = Fp64<MontBackend<FrConfig, 1>>;
+
+/// Wrapper around `Array2` so that matrices can be serialized and absorbed into a transcript.
+/// Encoded as `nrows || ncols || entries in row-major order`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix(pub Array2<F>);
+
+impl CanonicalSerialize for Matrix {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        let (rows, cols) = self.0.dim();
+        rows.serialize_with_mode(&mut writer, compress)?;
+        cols.serialize_with_mode(&mut writer, compress)?;
+        for entry in self.0.iter() {
+            entry.serialize_with_mode(&mut writer, compress)?;
+        }
+        Ok(())
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        let (rows, cols) = self.0.dim();
+        rows.serialized_size(compress)
+            + cols.serialized_size(compress)
+            + self
+                .0
+                .iter()
+                .map(|entry| entry.serialized_size(compress))
+                .sum::<usize>()
+    }
+}
 
-type F = Fp64<MontBackend<FrConfig, 1>>;
+impl FieldMessage for Matrix {
+    type Field = F;
+
+    fn entries_mut(&mut self) -> impl Iterator<Item = &mut F> {
+        self.0.iter_mut()
+    }
+}
+
+impl Valid for Matrix {
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for Matrix {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let rows = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        let cols = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        let len = rows
+            .checked_mul(cols)
+            .ok_or(SerializationError::InvalidData)?;
+        let entries = (0..len)
+            .map(|_| F::deserialize_with_mode(&mut reader, compress, validate))
+            .collect::<Result<Vec<_>, _>>()?;
+        Array2::from_shape_vec((rows, cols), entries)
+            .map(Matrix)
+            .map_err(|_| SerializationError::InvalidData)
+    }
+}
 
 // IP to verify that A*B = C for matrices A, B, and C
+pub struct MatMulIP;
+
+#[derive(Debug, Clone)]
+pub struct MatMulProver {
+    a_array: Array2<F>,
+    b_array: Array2<F>,
+}
+
 #[derive(Debug, Clone)]
-struct MatMulIP {
+pub struct MatMulVerifier {
     status: Status,
     a_array: Array2<F>,
     b_array: Array2<F>,
     c_array: Option<Array2<F>>,
-    prover_mode: ProverMode,
 }
 
 impl IP for MatMulIP {
-    type ProverMessage = Array2<F>;
+    type ProverMessage = Matrix;
     type VerifierMessage = ();
-    type Input = (Array2<F>, Array2<F>);
+    type Input = (Matrix, Matrix);
+    // Both parties know A and B, the prover claims C
+    type ProverInput = (Matrix, Matrix);
+    type VerifierInput = (Matrix, Matrix);
+    type Prover = MatMulProver;
+    type Verifier = MatMulVerifier;
+
+    fn split_input(input: Self::Input) -> (Self::ProverInput, Self::VerifierInput) {
+        (input.clone(), input)
+    }
+}
 
-    fn initialize(input: Self::Input, prover_mode: ProverMode) -> Self {
-        Self {
-            c_array: None,
-            status: Status::Running,
-            a_array: input.0,
-            b_array: input.1,
-            prover_mode,
-        }
+// C r and A B r differ by a nonzero polynomial of degree m - 1 in r for m the number of columns
+// of B, which has at most m - 1 roots
+impl SoundnessBound for MatMulIP {
+    fn soundness_error(input: &Self::Input) -> f64 {
+        input.1 .0.ncols().saturating_sub(1) as f64 / field_size::<F>()
     }
+}
 
-    fn run_honest_prover_logic(&mut self) -> Self::ProverMessage {
-        let c_array = self.a_array.dot(&self.b_array);
-        c_array
+impl Prover<MatMulIP> for MatMulProver {
+    fn initialize(input: (Matrix, Matrix)) -> Self {
+        let (Matrix(a_array), Matrix(b_array)) = input;
+        Self { a_array, b_array }
     }
 
-    fn run_malicious_prover_logic(&mut self) -> Self::ProverMessage {
-        let mut c_array = self.a_array.dot(&self.b_array);
-        // Manipulate one of the elements
-        c_array[(0, 0)] += F::one();
-        c_array
+    fn receive_verifier_message(&mut self, _message: ()) {}
+
+    fn run_prover_logic(&mut self) -> Matrix {
+        Matrix(self.a_array.dot(&self.b_array))
     }
+}
 
-    fn get_status(&self) -> Status {
-        self.status.clone()
+impl Verifier<MatMulIP> for MatMulVerifier {
+    fn initialize(input: (Matrix, Matrix)) -> Self {
+        let (Matrix(a_array), Matrix(b_array)) = input;
+        Self {
+            status: Status::Running,
+            a_array,
+            b_array,
+            c_array: None,
+        }
     }
 
-    fn run_verifier_logic(&mut self) -> Self::VerifierMessage {
-        let c_array = self.get_last_prover_message();
-        let mut rng = rand::thread_rng();
-        let mut r_powers = Array1::<F>::default(c_array.dim().0);
+    fn receive_prover_message(&mut self, message: Matrix) {
+        self.c_array = Some(message.0);
+    }
 
-        let r = F::rand(&mut rng);
+    fn run_verifier_logic<R: Rng>(&mut self, rng: &mut R) {
+        let r = F::rand(rng);
+        let c_array = self.c_array.as_ref().unwrap();
+        // The claimed C comes from the prover, so it may not even have the shape of A B
+        let (a_rows, a_cols) = self.a_array.dim();
+        let (b_rows, b_cols) = self.b_array.dim();
+        if a_cols != b_rows || c_array.dim() != (a_rows, b_cols) {
+            self.status = Status::Rejected;
+            return;
+        }
+
+        let mut r_powers = Array1::<F>::default(b_cols);
         let mut r_power = F::one();
         for power in r_powers.iter_mut() {
             *power = r_power;
@@ -73,46 +182,63 @@ impl IP for MatMulIP {
         }
     }
 
-    fn add_prover_message(&mut self, message: Self::ProverMessage) {
-        self.c_array = Some(message);
-    }
-
-    fn add_verifier_message(&mut self, _message: Self::VerifierMessage) {}
-
-    fn total_messages(&self) -> usize {
-        self.c_array.as_ref().map_or(0, |_| 1)
-    }
-
-    fn get_last_prover_message(&self) -> Self::ProverMessage {
-        self.c_array.clone().unwrap()
-    }
-
-    fn get_last_verifier_message(&self) -> Self::VerifierMessage {}
-
-    fn get_prover_mode(&self) -> ProverMode {
-        self.prover_mode.clone()
+    fn get_status(&self) -> Status {
+        self.status.clone()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::adversary::{CorruptEntry, Honest};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
 
     #[test]
     fn test_frievalds_ip() {
         let n = 20;
-        let a = Array2::<F>::from_elem((n, n), F::one());
-        let b = Array2::<F>::from_elem((n, n), F::one() + F::one());
+        let a = Matrix(Array2::<F>::from_elem((n, n), F::one()));
+        let b = Matrix(Array2::<F>::from_elem((n, n), F::one() + F::one()));
 
-        let mut honest_frievalds_ip =
-            MatMulIP::initialize((a.clone(), b.clone()), ProverMode::Honest);
+        let mut honest_frievalds_ip = Driver::<MatMulIP>::new((a.clone(), b.clone()));
         let _ = honest_frievalds_ip.step();
         let _ = honest_frievalds_ip.step();
         assert_eq!(honest_frievalds_ip.get_status(), Status::Accepted);
 
-        let mut malicious_sum_ip = MatMulIP::initialize((a, b), ProverMode::Malicious);
+        // Manipulate one of the elements
+        let mut malicious_sum_ip =
+            Driver::<MatMulIP, _>::with_adversary((a, b), CorruptEntry::every_round(0));
         let _ = malicious_sum_ip.step();
         let _ = malicious_sum_ip.step();
         assert_eq!(malicious_sum_ip.get_status(), Status::Rejected);
     }
+
+    #[test]
+    fn test_malformed_product() {
+        let a = Matrix(Array2::<F>::from_elem((4, 3), F::one()));
+        let b = Matrix(Array2::<F>::from_elem((3, 2), F::one() + F::one()));
+
+        // A B is 4 x 2, so r has one entry per column of C
+        let mut ip = Driver::<MatMulIP>::with_seed((a.clone(), b.clone()), Honest, 0);
+        assert_eq!(ip.run(), Status::Accepted);
+
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        for shape in [(4, 5), (2, 2), (2, 4)] {
+            let mut verifier = MatMulVerifier::initialize((a.clone(), b.clone()));
+            verifier.receive_prover_message(Matrix(Array2::from_elem(shape, F::one())));
+            verifier.run_verifier_logic(&mut rng);
+            assert_eq!(verifier.get_status(), Status::Rejected);
+        }
+    }
+
+    #[test]
+    fn test_matrix_deserialize_overflow() {
+        let mut bytes = vec![];
+        usize::MAX.serialize_compressed(&mut bytes).unwrap();
+        2usize.serialize_compressed(&mut bytes).unwrap();
+        assert!(matches!(
+            Matrix::deserialize_compressed(bytes.as_slice()),
+            Err(SerializationError::InvalidData)
+        ));
+    }
 }
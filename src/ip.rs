@@ -1,3 +1,10 @@
+use crate::{
+    adversary::{Adversary, Honest},
+    transcript::{Message, Transcript},
+};
+use rand::{thread_rng, Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Status {
     Running,
@@ -5,52 +12,166 @@ pub enum Status {
     Rejected,
 }
 
-#[derive(Debug, PartialEq, Clone)]
-pub enum ProverMode {
-    Honest,
-    Malicious,
+// Description of an interactive protocol: the messages exchanged and the two parties exchanging
+// them. Each party only gets its own part of the input, and afterwards only learns what the other
+// party sends it through the `Driver`.
+pub trait IP: Sized {
+    type ProverMessage: Clone;
+    type VerifierMessage: Clone;
+    // Full problem instance, which `split_input` divides into what each party gets to see.
+    type Input;
+    type ProverInput;
+    type VerifierInput;
+    type Prover: Prover<Self>;
+    type Verifier: Verifier<Self>;
+
+    fn split_input(input: Self::Input) -> (Self::ProverInput, Self::VerifierInput);
 }
 
-// TODO use a macro to make some base IP that pushes to a queue of messages
-pub trait IP {
-    type ProverMessage;
-    type VerifierMessage;
-    type Input;
+pub trait Prover<I: IP> {
+    fn initialize(input: I::ProverInput) -> Self;
+    fn receive_verifier_message(&mut self, message: I::VerifierMessage);
+    fn run_prover_logic(&mut self) -> I::ProverMessage;
+}
 
-    fn initialize(input: Self::Input, prover_mode: ProverMode) -> Self;
-    fn add_verifier_message(&mut self, message: Self::VerifierMessage);
-    fn add_prover_message(&mut self, message: Self::ProverMessage);
-    fn get_last_prover_message(&self) -> Self::ProverMessage;
-    fn get_last_verifier_message(&self) -> Self::VerifierMessage;
-    fn total_messages(&self) -> usize;
-    fn run_honest_prover_logic(&mut self) -> Self::ProverMessage;
-    fn run_malicious_prover_logic(&mut self) -> Self::ProverMessage;
-    fn run_verifier_logic(&mut self) -> Self::VerifierMessage;
+pub trait Verifier<I: IP> {
+    fn initialize(input: I::VerifierInput) -> Self;
+    fn receive_prover_message(&mut self, message: I::ProverMessage);
+    // All of the verifier's coins are drawn from `rng`, so that they can be replaced by
+    // transcript-derived challenges (see `fiat_shamir`).
+    fn run_verifier_logic<R: Rng>(&mut self, rng: &mut R) -> I::VerifierMessage;
     fn get_status(&self) -> Status;
-    fn get_prover_mode(&self) -> ProverMode;
+}
+
+// Runs a protocol by passing messages between its prover and verifier, starting with the prover.
+// Every message the prover sends goes through the adversary first, and everything sent is recorded
+// in the transcript. All randomness (the adversary's coins, and the seeds of the verifier's coins)
+// comes from a ChaCha rng seeded with `seed`, so any run can be reproduced with `with_seed`.
+pub struct Driver<I: IP, A: Adversary<I> = Honest> {
+    pub prover: I::Prover,
+    pub verifier: I::Verifier,
+    pub adversary: A,
+    // Print the seed when the verifier rejects
+    pub log_rejections: bool,
+    seed: u64,
+    rng: ChaCha20Rng,
+    transcript: Transcript<I::ProverMessage, I::VerifierMessage>,
+}
+
+impl<I: IP> Driver<I> {
+    pub fn new(input: I::Input) -> Self {
+        Self::with_adversary(input, Honest)
+    }
+}
+
+impl<I: IP, A: Adversary<I>> Driver<I, A> {
+    pub fn with_adversary(input: I::Input, adversary: A) -> Self {
+        Self::with_seed(input, adversary, thread_rng().gen())
+    }
+
+    pub fn with_seed(input: I::Input, adversary: A, seed: u64) -> Self {
+        let (prover_input, verifier_input) = I::split_input(input);
+        Self::from_parts(
+            I::Prover::initialize(prover_input),
+            I::Verifier::initialize(verifier_input),
+            adversary,
+            seed,
+        )
+    }
+
+    pub fn from_parts(prover: I::Prover, verifier: I::Verifier, adversary: A, seed: u64) -> Self {
+        Self {
+            prover,
+            verifier,
+            adversary,
+            log_rejections: true,
+            seed,
+            rng: ChaCha20Rng::seed_from_u64(seed),
+            transcript: Transcript::new(),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn get_status(&self) -> Status {
+        self.verifier.get_status()
+    }
+
+    pub fn transcript(&self) -> &Transcript<I::ProverMessage, I::VerifierMessage> {
+        &self.transcript
+    }
+
+    pub fn into_transcript(self) -> Transcript<I::ProverMessage, I::VerifierMessage> {
+        self.transcript
+    }
 
-    fn step(&mut self) -> Status {
+    pub fn total_messages(&self) -> usize {
+        self.transcript.entries.len()
+    }
+
+    pub fn step(&mut self) -> Status {
         let status = self.get_status();
         // If decided, return
         if status != Status::Running {
             return status;
         }
 
-        let num_messages = self.total_messages();
-
-        if num_messages % 2 == 0 {
-            let prover_message = match self.get_prover_mode() {
-                ProverMode::Honest => self.run_honest_prover_logic(),
-                ProverMode::Malicious => self.run_malicious_prover_logic(),
-            };
-            self.add_prover_message(prover_message);
+        if self.total_messages().is_multiple_of(2) {
+            self.prover_turn();
         } else {
-            let verifier_message = self.run_verifier_logic();
-            self.add_verifier_message(verifier_message);
+            let seed = self.rng.gen();
+            self.verifier_turn(seed);
         }
 
+        let status = self.get_status();
+        if status == Status::Rejected && self.log_rejections {
+            eprintln!(
+                "Verifier rejected in round {}, rerun with seed {}",
+                (self.total_messages() - 1) / 2,
+                self.seed
+            );
+        }
+        status
+    }
+
+    // Steps until the verifier decides
+    pub fn run(&mut self) -> Status {
+        while self.step() == Status::Running {}
         self.get_status()
     }
+
+    // Sends the (possibly tampered with) prover message for the current round to the verifier.
+    pub fn prover_turn(&mut self) -> &I::ProverMessage {
+        let honest_message = self.prover.run_prover_logic();
+        let prover_message =
+            self.adversary
+                .intercept(honest_message, &self.transcript, &mut self.rng);
+        self.transcript.push(
+            self.transcript.current_round(),
+            Message::Prover(prover_message.clone()),
+        );
+        self.verifier.receive_prover_message(prover_message.clone());
+        self.transcript.prover_messages().last().unwrap()
+    }
+
+    // Runs the verifier with coins from a ChaCha rng seeded by `seed`. The seed is recorded in the
+    // transcript so the run can be replayed.
+    pub fn verifier_turn(&mut self, seed: [u8; 32]) -> &I::VerifierMessage {
+        let verifier_message = self
+            .verifier
+            .run_verifier_logic(&mut ChaCha20Rng::from_seed(seed));
+        self.transcript.push(
+            self.transcript.current_round(),
+            Message::Verifier {
+                message: verifier_message.clone(),
+                seed,
+            },
+        );
+        self.prover.receive_verifier_message(verifier_message);
+        self.transcript.verifier_messages().last().unwrap()
+    }
 }
 
 #[cfg(test)]
@@ -58,42 +179,72 @@ mod tests {
     use super::*;
     // Super simple IP where the prover accepts if the verifier can submit two numbers that sum to
     // a given target
-    #[derive(Debug, Clone)]
-    struct SumIP {
+    struct SumIP;
+
+    struct SumProver {
         target_sum: u64,
-        messages: Vec<(u64, u64)>,
+    }
+
+    struct SumVerifier {
+        target_sum: u64,
+        last_message: Option<(u64, u64)>,
         status: Status,
-        prover_mode: ProverMode,
     }
 
     impl IP for SumIP {
         type ProverMessage = (u64, u64);
         type VerifierMessage = ();
         type Input = u64;
+        type ProverInput = u64;
+        type VerifierInput = u64;
+        type Prover = SumProver;
+        type Verifier = SumVerifier;
 
-        fn initialize(input: Self::Input, prover_mode: ProverMode) -> Self {
-            Self {
-                target_sum: input,
-                messages: vec![],
-                status: Status::Running,
-                prover_mode,
-            }
+        fn split_input(input: Self::Input) -> (Self::ProverInput, Self::VerifierInput) {
+            (input, input)
         }
+    }
+
+    impl Prover<SumIP> for SumProver {
+        fn initialize(input: u64) -> Self {
+            Self { target_sum: input }
+        }
+
+        fn receive_verifier_message(&mut self, _message: ()) {}
 
-        fn run_honest_prover_logic(&mut self) -> Self::ProverMessage {
+        fn run_prover_logic(&mut self) -> (u64, u64) {
             (1, self.target_sum - 1)
         }
+    }
+
+    struct BumpFirst;
 
-        fn run_malicious_prover_logic(&mut self) -> Self::ProverMessage {
-            (2, self.target_sum - 1)
+    impl Adversary<SumIP> for BumpFirst {
+        fn intercept<R: Rng>(
+            &mut self,
+            honest_message: (u64, u64),
+            _transcript: &Transcript<(u64, u64), ()>,
+            _rng: &mut R,
+        ) -> (u64, u64) {
+            (honest_message.0 + 1, honest_message.1)
         }
+    }
 
-        fn get_status(&self) -> Status {
-            self.status.clone()
+    impl Verifier<SumIP> for SumVerifier {
+        fn initialize(input: u64) -> Self {
+            Self {
+                target_sum: input,
+                last_message: None,
+                status: Status::Running,
+            }
         }
 
-        fn run_verifier_logic(&mut self) -> Self::VerifierMessage {
-            let last_message = self.get_last_prover_message();
+        fn receive_prover_message(&mut self, message: (u64, u64)) {
+            self.last_message = Some(message);
+        }
+
+        fn run_verifier_logic<R: Rng>(&mut self, _rng: &mut R) {
+            let last_message = self.last_message.unwrap();
             if last_message.0 + last_message.1 == self.target_sum {
                 self.status = Status::Accepted;
             } else {
@@ -101,35 +252,19 @@ mod tests {
             }
         }
 
-        fn add_prover_message(&mut self, message: Self::ProverMessage) {
-            self.messages.push(message);
-        }
-
-        fn add_verifier_message(&mut self, message: Self::VerifierMessage) {}
-
-        fn total_messages(&self) -> usize {
-            self.messages.len()
-        }
-
-        fn get_last_prover_message(&self) -> Self::ProverMessage {
-            *self.messages.last().unwrap()
-        }
-
-        fn get_last_verifier_message(&self) -> Self::VerifierMessage {}
-
-        fn get_prover_mode(&self) -> ProverMode {
-            self.prover_mode.clone()
+        fn get_status(&self) -> Status {
+            self.status.clone()
         }
     }
 
     #[test]
     fn test_sum_ip() {
-        let mut honest_sum_ip = SumIP::initialize(100, ProverMode::Honest);
+        let mut honest_sum_ip = Driver::<SumIP>::new(100);
         let _ = honest_sum_ip.step();
         let _ = honest_sum_ip.step();
         assert_eq!(honest_sum_ip.get_status(), Status::Accepted);
 
-        let mut malicious_sum_ip = SumIP::initialize(100, ProverMode::Malicious);
+        let mut malicious_sum_ip = Driver::<SumIP, _>::with_adversary(100, BumpFirst);
         let _ = malicious_sum_ip.step();
         let _ = malicious_sum_ip.step();
         assert_eq!(malicious_sum_ip.get_status(), Status::Rejected);
@@ -0,0 +1,232 @@
+use crate::{fields::distinct_points, sumcheck::SumCheckPolynomial};
+use ark_ff::Field;
+
+// Values at x of the univariate Lagrange basis for the points {0, ..., m - 1}: entry k is
+// chi_k(x) = prod_{j != k} (x - j) / (k - j), which is 1 at x = k and 0 at the other points.
+pub fn lagrange_basis<F: Field>(x: F, m: usize) -> Vec<F> {
+    (0..m)
+        .map(|k| {
+            let mut numerator = F::one();
+            let mut denominator = F::one();
+            for j in (0..m).filter(|j| *j != k) {
+                numerator *= x - F::from(j as u64);
+                denominator *= F::from(k as u64) - F::from(j as u64);
+            }
+            numerator * denominator.inverse().unwrap()
+        })
+        .collect()
+}
+
+// Digit i of `index` in base m gives x_{i+1}, as bit i does for the hypercube
+pub fn index_to_grid<F: Field>(mut index: usize, m: usize, v: usize) -> Vec<F> {
+    assert!(index < m.pow(v as u32));
+    let mut point = Vec::with_capacity(v);
+    for _ in 0..v {
+        point.push(F::from((index % m) as u64));
+        index /= m;
+    }
+    point
+}
+
+// The Lagrange basis polynomial for the grid point `index` evaluated at r: the product over the
+// variables of chi_{k_i}(r_i), where k_i is digit i of the index. With m = 2 this is
+// `mle::w_basis_eval`.
+pub fn grid_basis_eval<F: Field>(r: &[F], index: usize, m: usize) -> F {
+    let mut index = index;
+    let mut prod = F::one();
+    for r_i in r {
+        prod *= lagrange_basis(*r_i, m)[index % m];
+        index /= m;
+    }
+    prod
+}
+
+// Low-degree extension of a function on {0, ..., m - 1}^v: the unique polynomial of degree at most
+// m - 1 in each variable that agrees with it on the grid. Stored as the grid evaluations, indexed
+// as in `index_to_grid`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LDE<F: Field> {
+    evals: Vec<F>,
+    num_vars: usize,
+    domain_size: usize,
+}
+
+impl<F: Field> LDE<F> {
+    // `evals` has m^v entries. The points 0, ..., m - 1 have to be distinct in F.
+    pub fn new(domain_size: usize, evals: Vec<F>) -> Self {
+        let m = domain_size;
+        assert!(m >= 2);
+        assert!(distinct_points::<F>(m));
+        let mut num_vars = 0;
+        let mut size = 1;
+        while size < evals.len() {
+            size *= m;
+            num_vars += 1;
+        }
+        assert_eq!(size, evals.len());
+        Self {
+            evals,
+            num_vars,
+            domain_size,
+        }
+    }
+
+    pub fn evals(&self) -> &[F] {
+        &self.evals
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    pub fn domain_size(&self) -> usize {
+        self.domain_size
+    }
+
+    // Folds one variable at a time, in O(m * m^v) field operations
+    pub fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(point.len(), self.num_vars);
+        point
+            .iter()
+            .fold(self.clone(), |lde, r| lde.fix_variable(*r))
+            .evals[0]
+    }
+
+    // Fixes x1 to r, dividing the table by m
+    pub fn fix_variable(&self, r: F) -> Self {
+        assert!(self.num_vars > 0);
+        let basis = lagrange_basis(r, self.domain_size);
+        let evals = self
+            .evals
+            .chunks(self.domain_size)
+            .map(|line| line.iter().zip(&basis).map(|(eval, chi)| *eval * chi).sum())
+            .collect();
+        Self {
+            evals,
+            num_vars: self.num_vars - 1,
+            domain_size: self.domain_size,
+        }
+    }
+}
+
+impl<F: Field> SumCheckPolynomial<F> for LDE<F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn degrees(&self) -> Vec<usize> {
+        vec![self.domain_size - 1; self.num_vars]
+    }
+
+    fn evaluate(&self, point: &[F]) -> F {
+        self.evaluate(point)
+    }
+
+    fn domain_size(&self) -> usize {
+        self.domain_size
+    }
+
+    fn fix_first_variable(&self, r: F) -> Self {
+        self.fix_variable(r)
+    }
+
+    // Sums the rest of the grid for each value of x1, then extends the m sums to the other points
+    fn first_variable_evals(&self, num_points: usize) -> Vec<F> {
+        let m = self.domain_size;
+        let mut sums = vec![F::zero(); m];
+        for (index, eval) in self.evals.iter().enumerate() {
+            sums[index % m] += eval;
+        }
+        (0..num_points)
+            .map(|x| {
+                let basis = lagrange_basis(F::from(x as u64), m);
+                sums.iter().zip(&basis).map(|(sum, chi)| *sum * chi).sum()
+            })
+            .collect()
+    }
+
+    // The sum over the whole grid
+    fn hypercube_sum(&self) -> F {
+        self.evals.iter().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        adversary::{CorruptEntry, LieInRound},
+        fields::{random_vec, Fr},
+        ip::{Driver, Status},
+        mle::{dense_eval, w_basis_eval},
+        sumcheck::{verify_sumcheck, SumCheck, SumcheckProof},
+    };
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_lde_agrees_on_grid() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let (m, v) = (3, 3);
+        let lde = LDE::new(m, random_vec::<Fr, _>(27, &mut rng));
+        assert_eq!(lde.num_vars(), v);
+        for (index, eval) in lde.evals().iter().enumerate() {
+            assert_eq!(lde.evaluate(&index_to_grid(index, m, v)), *eval);
+        }
+
+        // Away from the grid, folding agrees with summing over the basis
+        let r = random_vec::<Fr, _>(v, &mut rng);
+        let expected: Fr = lde
+            .evals()
+            .iter()
+            .enumerate()
+            .map(|(index, eval)| *eval * grid_basis_eval(&r, index, m))
+            .sum();
+        assert_eq!(lde.evaluate(&r), expected);
+    }
+
+    #[test]
+    fn test_boolean_grid_is_mle() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let evals = random_vec::<Fr, _>(8, &mut rng);
+        let r = random_vec::<Fr, _>(3, &mut rng);
+        assert_eq!(
+            LDE::new(2, evals.clone()).evaluate(&r),
+            dense_eval(&r, &evals)
+        );
+        for (index, eval) in evals.iter().enumerate() {
+            assert_eq!(
+                grid_basis_eval(&r, index, 2),
+                w_basis_eval(&r, (index, *eval), 3)
+            );
+        }
+    }
+
+    #[test]
+    fn test_grid_sumcheck() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let lde = LDE::new(4, random_vec::<Fr, _>(64, &mut rng));
+        let mut ip = Driver::<SumCheck<Fr, LDE<Fr>>>::new(lde.clone());
+        assert_eq!(ip.run(), Status::Accepted);
+        // Messages have m evaluations for degree m - 1
+        assert!(ip.transcript().prover_messages().all(|m| m.len() == 4));
+        let (proof, challenges) = SumcheckProof::from_transcript(ip.transcript());
+        let subclaim =
+            verify_sumcheck(lde.hypercube_sum(), &lde.degrees(), 4, &proof, &challenges).unwrap();
+        assert_eq!(lde.evaluate(&subclaim.point), subclaim.expected_value);
+
+        let mut ip = Driver::<SumCheck<Fr, LDE<Fr>>, _>::with_adversary(
+            lde.clone(),
+            CorruptEntry::in_round(0, 3),
+        );
+        ip.log_rejections = false;
+        assert_eq!(ip.run(), Status::Rejected);
+
+        // Adding 2X - 1 keeps a sum over {0, 1} but not over {0, ..., 3}
+        let mut ip =
+            Driver::<SumCheck<Fr, LDE<Fr>>, _>::with_adversary(lde, LieInRound { round: 0 });
+        ip.log_rejections = false;
+        assert_eq!(ip.run(), Status::Rejected);
+        assert_eq!(ip.transcript().entries.len(), 2);
+    }
+}
@@ -0,0 +1,16 @@
+pub mod adversary;
+pub mod fiat_shamir;
+pub mod fields;
+pub mod freivalds;
+pub mod ip;
+pub mod lde;
+pub mod mle;
+pub mod oracle;
+pub mod parser;
+pub mod polynomials;
+pub mod reed_solomon;
+pub mod soundness;
+pub mod sumcheck;
+pub mod transcript;
+pub mod virtual_polynomial;
+pub mod zerocheck;
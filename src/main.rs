@@ -1,14 +1,10 @@
 use ark_ff::{Field, PrimeField};
 use ark_std::{rand::Rng, UniformRand};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
 
-pub mod fields;
-pub mod freivalds;
-pub mod ip;
-pub mod mle;
-pub mod polynomials;
-pub mod sumcheck;
-
-use fields::Fr;
+use ark_poly::polynomial::multivariate::{SparsePolynomial, SparseTerm};
+use thaler::{adversary, fields::Fr, mle, parser, soundness, sumcheck};
 
 #[derive(Debug, Clone)]
 struct HashFn {
@@ -41,9 +37,11 @@ impl HashFn {
     }
 }
 
-fn run_reed_solomon_communication_protocol(bob_file: Vec<u64>, alice_file: Vec<u64>) -> bool {
-    let mut rng = rand::thread_rng();
-
+fn run_reed_solomon_communication_protocol<R: Rng>(
+    bob_file: Vec<u64>,
+    alice_file: Vec<u64>,
+    rng: &mut R,
+) -> bool {
     // Alice and Bob have a file of length n
     if bob_file.len() != alice_file.len() {
         return false;
@@ -59,7 +57,7 @@ fn run_reed_solomon_communication_protocol(bob_file: Vec<u64>, alice_file: Vec<u
         .collect();
 
     // Alice will generate a hash function from the family Hr
-    let alice_hash = HashFn::new(&mut rng);
+    let alice_hash = HashFn::new(rng);
 
     // Alice generates her fingerprint
     let alice_fingerprint = alice_hash.hash(alice_evals);
@@ -78,13 +76,13 @@ fn run_reed_solomon_communication_protocol(bob_file: Vec<u64>, alice_file: Vec<u
     true
 }
 
-fn test_differing_fingerprints(num_elems: usize, num_runs: usize) {
+fn test_differing_fingerprints<R: Rng>(num_elems: usize, num_runs: usize, rng: &mut R) {
     let mut failures = 0;
     for _ in 0..num_runs {
         let alice_file: Vec<u64> = (0..num_elems).map(|_| 0).collect();
         let mut bob_file = alice_file.clone();
         bob_file[0] += 1;
-        let success = run_reed_solomon_communication_protocol(alice_file, bob_file);
+        let success = run_reed_solomon_communication_protocol(alice_file, bob_file, rng);
         if !success {
             failures += 1;
         }
@@ -96,10 +94,50 @@ fn test_differing_fingerprints(num_elems: usize, num_runs: usize) {
     )
 }
 
+fn test_sumcheck_soundness<R: Rng>(v: usize, num_runs: usize, rng: &mut R) {
+    let evals = mle::random_evals::<Fr, _>(v, rng);
+    let report = soundness::estimate_soundness::<sumcheck::SumCheck<Fr>, _, _>(
+        &evals,
+        &adversary::LieInRound { round: 0 },
+        num_runs,
+        rng,
+    );
+    println!("Sum Check Soundness. \n {} \n", report)
+}
+
+fn test_polynomial_sumcheck_soundness<R: Rng>(input: &str, num_runs: usize, rng: &mut R) {
+    let poly = match parser::parse_polynomial::<Fr>(input) {
+        Ok(poly) => poly,
+        Err(err) => {
+            println!("Could not parse {:?}: {} \n", input, err);
+            return;
+        }
+    };
+    let report = soundness::estimate_soundness::<
+        sumcheck::SumCheck<Fr, SparsePolynomial<Fr, SparseTerm>>,
+        _,
+        _,
+    >(&poly, &adversary::LieInRound { round: 0 }, num_runs, rng);
+    println!("Sum Check Soundness for {}. \n {} \n", input, report)
+}
+
 fn main() {
-    test_differing_fingerprints(12, 10000);
+    // Pass a seed as the first argument to rerun an earlier experiment, and a polynomial such as
+    // "2*x1^3 + x1*x3 + x2*x3" as the second to run sumcheck on it
+    let seed = std::env::args()
+        .nth(1)
+        .map(|seed| seed.parse().expect("seed should be a u64"))
+        .unwrap_or_else(|| rand::thread_rng().gen());
+    println!("Seed: {} \n", seed);
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+
+    test_differing_fingerprints(12, 10000, &mut rng);
+    test_sumcheck_soundness(4, 10000, &mut rng);
+    if let Some(poly) = std::env::args().nth(2) {
+        test_polynomial_sumcheck_soundness(&poly, 10000, &mut rng);
+    }
     // let v = 2;
-    // let evals = random_evals::<Fr>(v);
+    // let evals = random_evals::<Fr, _>(v, &mut rng);
     // dbg!(evals);
     // dbg!(index_to_bools(31, 5));
 }
@@ -1,9 +1,63 @@
-use crate::fields::bool_to_field;
+use crate::{fields::bool_to_field, sumcheck::SumCheckPolynomial};
 use ark_ff::Field;
-use rand::thread_rng;
+use ark_poly::DenseMultilinearExtension;
+use ark_std::{cfg_chunks, cfg_into_iter, cfg_iter, collections::BTreeMap, rc::Rc, sync::Arc};
+use ndarray::Array2;
+use rand::Rng;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 // We need 2^v evaluation points
 
+// How the bits of a hypercube index map to the variables. Bare slices of evaluations, as taken by
+// the functions in this module, are always little-endian.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VariableOrder {
+    // Bit i of the index gives x_{i+1}, as in `index_to_vars` and `ark_poly`
+    #[default]
+    LittleEndian,
+    // Bit v - 1 - i of the index gives x_{i+1}, so x1 is the most significant bit
+    BigEndian,
+}
+
+impl VariableOrder {
+    // Bit of a v-bit index that gives variable `var`, counting x1 as variable 0
+    pub fn bit(self, var: usize, v: usize) -> usize {
+        match self {
+            VariableOrder::LittleEndian => var,
+            VariableOrder::BigEndian => v - 1 - var,
+        }
+    }
+
+    pub fn index_to_vars<F: Field>(self, index: usize, v: usize) -> Vec<F> {
+        let mut vars = index_to_vars(index, v);
+        if self == VariableOrder::BigEndian {
+            vars.reverse();
+        }
+        vars
+    }
+}
+
+// Reverses the low v bits of `index`, which maps an index in one order to the other
+pub fn reverse_bits(index: usize, v: usize) -> usize {
+    if v == 0 {
+        return 0;
+    }
+    index.reverse_bits() >> (usize::BITS as usize - v)
+}
+
+// Rearranges hypercube evaluations listed in order `from` into order `to`
+pub fn convert_order<F: Copy>(evals: &[F], from: VariableOrder, to: VariableOrder) -> Vec<F> {
+    assert!(evals.len().is_power_of_two());
+    if from == to {
+        return evals.to_vec();
+    }
+    let v = evals.len().trailing_zeros() as usize;
+    (0..evals.len())
+        .map(|index| evals[reverse_bits(index, v)])
+        .collect()
+}
+
 pub fn index_to_vars<F: Field>(mut index: usize, v: usize) -> Vec<F> {
     let mut vars_vec = vec![F::zero(); v];
     let mut place = 0;
@@ -16,11 +70,9 @@ pub fn index_to_vars<F: Field>(mut index: usize, v: usize) -> Vec<F> {
     vars_vec
 }
 
-pub fn random_evals<F: Field>(v: usize) -> Vec<F> {
-    let mut rng = thread_rng();
-    (0..2_usize.pow(v as u32))
-        .map(|_| F::rand(&mut rng))
-        .collect()
+// Stays sequential with the `parallel` feature, so that a seed gives the same evaluations either way
+pub fn random_evals<F: Field, R: Rng>(v: usize, rng: &mut R) -> Vec<F> {
+    (0..2_usize.pow(v as u32)).map(|_| F::rand(rng)).collect()
 }
 
 pub fn w_basis_eval<F: Field>(r: &[F], eval: (usize, F), v: usize) -> F {
@@ -34,14 +86,46 @@ pub fn w_basis_eval<F: Field>(r: &[F], eval: (usize, F), v: usize) -> F {
     prod
 }
 
-pub fn stream_eval<F: Field>(r: &[F], evals: &[F], v: usize) -> F {
-    let mut res = F::zero();
+// eq(r, x) = prod_i (r_i x_i + (1 - r_i)(1 - x_i)), the multilinear polynomial that is 1 when
+// x = r on the hypercube and 0 elsewhere. Takes O(v) field operations at any point x.
+pub fn eq_eval<F: Field>(r: &[F], x: &[F]) -> F {
+    assert_eq!(r.len(), x.len());
+    r.iter()
+        .zip(x)
+        .map(|(r_i, x_i)| *r_i * x_i + (F::one() - r_i) * (F::one() - x_i))
+        .product()
+}
 
-    for (i, eval) in evals.iter().enumerate() {
-        res += evals[i] * w_basis_eval(r, (i, *eval), v);
+// Hypercube evaluations of eq(r, x), i.e. the Lagrange basis at r, in O(2^v) by memoizing the
+// products over the first k variables and extending them one variable at a time (Thaler Lemma 3.8)
+pub fn eq_evals<F: Field>(r: &[F]) -> Vec<F> {
+    let mut table = Vec::with_capacity(1 << r.len());
+    table.push(F::one());
+    for r_k in r {
+        // Variable k + 1 is bit k, so the entries with it set go after those without
+        let with_zero: Vec<F> = table.iter().map(|eq| *eq * (F::one() - r_k)).collect();
+        let with_one: Vec<F> = table.iter().map(|eq| *eq * r_k).collect();
+        table = [with_zero, with_one].concat();
     }
+    table
+}
 
-    res
+// Evaluates the multilinear extension of `evals` at r as its inner product with the Lagrange
+// basis at r, in O(2^v) time and space. `stream_eval` takes O(v * 2^v) time but O(v) space.
+pub fn dense_eval<F: Field>(r: &[F], evals: &[F]) -> F {
+    assert_eq!(evals.len(), 1 << r.len());
+    let eq = eq_evals(r);
+    cfg_iter!(evals)
+        .zip(cfg_iter!(eq))
+        .map(|(eval, eq)| *eval * eq)
+        .sum()
+}
+
+pub fn stream_eval<F: Field>(r: &[F], evals: &[F], v: usize) -> F {
+    cfg_iter!(evals)
+        .enumerate()
+        .map(|(i, eval)| *eval * w_basis_eval(r, (i, *eval), v))
+        .sum()
 }
 
 pub fn g_poly<F: Field>(input: &[F]) -> F {
@@ -49,40 +133,597 @@ pub fn g_poly<F: Field>(input: &[F]) -> F {
     assert!(input.len() == 3);
 
     // Use example in book 2X1^3 + X1X3 + X2X3
-    F::from(2u64) * input[0].pow([3u64]) + input[1] * input[2] + input[1] * input[2]
+    F::from(2u64) * input[0].pow([3u64]) + input[0] * input[2] + input[1] * input[2]
 }
 
 // Given a list of evals, calculate the univariate polynomial for variable xi
 // With x1,..xi-1 fixed with random values and xi+1 summed away.
 //
-// Returns |F| evaluations of the resulting univariate polynomial
-//
-// Cant verifier check in constant time? Simply by observing that all of the evaluation points are
-// distinct - guess this would be O(|F|)
-//
-// But |F| is large - so refactor this to simply return a single evaluation so that the verifier
-// can calculate the degree 1 univariate polynomial (TODO)
-pub fn calculate_g_i<F: Field>(randoms: &[F], evals: &[F], v: usize) -> Vec<F> {
+// Returns the evaluations of the resulting univariate polynomial at 0, ..., num_points - 1. The
+// polynomial has degree 1, so num_points = 2 is enough for the verifier to recover it.
+pub fn calculate_g_i<F: Field>(randoms: &[F], evals: &[F], v: usize, num_points: usize) -> Vec<F> {
     let mut res = vec![];
-    // TODO: hardcoded field  field size, find some way to fix this
-    for i in 0..5 {
-        let mut partial_sum = F::zero();
-        let total_indices = 2u32.pow((v - randoms.len() - 1) as u32);
-        for index in 0..total_indices {
-            let vars = index_to_vars(index as usize, v - randoms.len() - 1);
-            let r = [randoms, &[F::from(i as u32)], &vars].concat();
-            let eval = stream_eval(&r, evals, v);
-            partial_sum += eval;
-        }
+    for i in 0..num_points {
+        let total_indices = 2usize.pow((v - randoms.len() - 1) as u32);
+        let partial_sum = cfg_into_iter!(0..total_indices)
+            .map(|index| {
+                let vars = index_to_vars(index, v - randoms.len() - 1);
+                let r = [randoms, &[F::from(i as u32)], &vars].concat();
+                stream_eval(&r, evals, v)
+            })
+            .sum();
         res.push(partial_sum);
     }
     res
 }
 
+// Fixes the first variable of the multilinear polynomial with hypercube evaluations `evals` to r,
+// halving the table: f(r, x2, ..., xv) = f(0, x2, ..., xv) + r * (f(1, x2, ..., xv) - f(0, x2, ..., xv))
+pub fn fix_first_variable<F: Field>(evals: &[F], r: F) -> Vec<F> {
+    cfg_chunks!(evals, 2)
+        .map(|pair| pair[0] + r * (pair[1] - pair[0]))
+        .collect()
+}
+
+// Evaluations at 0, ..., num_points - 1 of the univariate polynomial in the first variable with
+// the rest summed away, in time linear in the size of the table. Same as `calculate_g_i` when
+// `evals` is the table with the random values already fixed by `fix_first_variable`.
+pub fn first_variable_evals<F: Field>(evals: &[F], num_points: usize) -> Vec<F> {
+    let sum_0: F = cfg_chunks!(evals, 2).map(|pair| pair[0]).sum();
+    let sum_1: F = cfg_chunks!(evals, 2).map(|pair| pair[1]).sum();
+    // The polynomial is linear, so it is determined by its values at 0 and 1
+    (0..num_points)
+        .map(|x| sum_0 + F::from(x as u64) * (sum_1 - sum_0))
+        .collect()
+}
+
+// A multilinear polynomial in `num_vars` variables, stored as its evaluations over the boolean
+// hypercube with the index bits mapped to variables by `order`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DenseMLE<F: Field> {
+    evals: Vec<F>,
+    num_vars: usize,
+    order: VariableOrder,
+}
+
+impl<F: Field> DenseMLE<F> {
+    pub fn new(evals: Vec<F>) -> Self {
+        Self::with_order(evals, VariableOrder::LittleEndian)
+    }
+
+    pub fn with_order(evals: Vec<F>, order: VariableOrder) -> Self {
+        assert!(evals.len().is_power_of_two());
+        Self {
+            num_vars: evals.len().trailing_zeros() as usize,
+            evals,
+            order,
+        }
+    }
+
+    // Tabulates f over the hypercube, where bit i of the argument is x_{i+1}
+    pub fn from_fn(num_vars: usize, f: impl Fn(&[bool]) -> F) -> Self {
+        let evals = (0..1 << num_vars)
+            .map(|index: usize| {
+                let bits: Vec<bool> = (0..num_vars).map(|i| (index >> i) & 1 == 1).collect();
+                f(&bits)
+            })
+            .collect();
+        Self::new(evals)
+    }
+
+    // f(i, j) = A[i][j] with the bits of the row index i as the first block of variables and the
+    // bits of the column index j as the second, each dimension padded with zeros to a power of
+    // two. Fixing variables therefore fixes the row first; use the transpose to fix the column.
+    pub fn from_matrix(matrix: &Array2<F>) -> Self {
+        let (row_vars, col_vars) = matrix_num_vars(matrix);
+        let rows = 1 << row_vars;
+        let mut evals = vec![F::zero(); rows << col_vars];
+        for ((i, j), entry) in matrix.indexed_iter() {
+            evals[i + rows * j] = *entry;
+        }
+        Self::new(evals)
+    }
+
+    // One evaluation per byte, padded with zeros to a power of two. Bytes are reduced mod p, so
+    // this is only injective for fields with at least 256 elements.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let mut evals: Vec<F> = bytes.iter().map(|byte| F::from(*byte as u64)).collect();
+        evals.resize(bytes.len().max(1).next_power_of_two(), F::zero());
+        Self::new(evals)
+    }
+
+    pub fn evals(&self) -> &[F] {
+        &self.evals
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    pub fn order(&self) -> VariableOrder {
+        self.order
+    }
+
+    // The same polynomial with its table rearranged into `order`
+    pub fn to_order(&self, order: VariableOrder) -> Self {
+        Self::with_order(convert_order(&self.evals, self.order, order), order)
+    }
+
+    pub fn evaluate(&self, point: &[F]) -> F {
+        match self.order {
+            VariableOrder::LittleEndian => dense_eval(point, &self.evals),
+            VariableOrder::BigEndian => {
+                let reversed: Vec<F> = point.iter().rev().copied().collect();
+                dense_eval(&reversed, &self.evals)
+            }
+        }
+    }
+
+    // Fixes x1 to r, halving the table
+    pub fn fix_variable(&self, r: F) -> Self {
+        assert!(self.num_vars > 0);
+        let evals = match self.order {
+            VariableOrder::LittleEndian => fix_first_variable(&self.evals, r),
+            // x1 is the top bit, so the table splits into x1 = 0 and x1 = 1 halves
+            VariableOrder::BigEndian => {
+                let (low, high) = self.evals.split_at(self.evals.len() / 2);
+                low.iter()
+                    .zip(high)
+                    .map(|(a, b)| *a + r * (*b - a))
+                    .collect()
+            }
+        };
+        Self {
+            evals,
+            num_vars: self.num_vars - 1,
+            order: self.order,
+        }
+    }
+}
+
+impl<F: Field> SumCheckPolynomial<F> for DenseMLE<F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn degrees(&self) -> Vec<usize> {
+        vec![1; self.num_vars]
+    }
+
+    fn evaluate(&self, point: &[F]) -> F {
+        DenseMLE::evaluate(self, point)
+    }
+
+    fn fix_first_variable(&self, r: F) -> Self {
+        self.fix_variable(r)
+    }
+
+    fn first_variable_evals(&self, num_points: usize) -> Vec<F> {
+        match self.order {
+            VariableOrder::LittleEndian => first_variable_evals(&self.evals, num_points),
+            VariableOrder::BigEndian => {
+                let (low, high) = self.evals.split_at(self.evals.len() / 2);
+                let (sum_0, sum_1): (F, F) = (low.iter().sum(), high.iter().sum());
+                (0..num_points)
+                    .map(|x| sum_0 + F::from(x as u64) * (sum_1 - sum_0))
+                    .collect()
+            }
+        }
+    }
+
+    fn hypercube_sum(&self) -> F {
+        self.evals.iter().sum()
+    }
+}
+
+// Number of row and column variables in `DenseMLE::from_matrix`
+pub fn matrix_num_vars<F>(matrix: &Array2<F>) -> (usize, usize) {
+    let (rows, cols) = matrix.dim();
+    let num_vars = |n: usize| n.max(1).next_power_of_two().trailing_zeros() as usize;
+    (num_vars(rows), num_vars(cols))
+}
+
+// `ark_poly` lists evaluations in little-endian order
+impl<F: Field> From<DenseMultilinearExtension<F>> for DenseMLE<F> {
+    fn from(poly: DenseMultilinearExtension<F>) -> Self {
+        Self::with_order(poly.evaluations, VariableOrder::LittleEndian)
+    }
+}
+
+impl<F: Field> From<DenseMLE<F>> for DenseMultilinearExtension<F> {
+    fn from(mle: DenseMLE<F>) -> Self {
+        let mle = mle.to_order(VariableOrder::LittleEndian);
+        DenseMultilinearExtension::from_evaluations_vec(mle.num_vars, mle.evals)
+    }
+}
+
+// A multilinear polynomial with few nonzero evaluations over the hypercube, keyed by hypercube
+// index. Evaluation and every round of the sumcheck prover take time proportional to the number
+// of nonzero entries rather than 2^v.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseMLE<F: Field> {
+    entries: BTreeMap<usize, F>,
+    num_vars: usize,
+    order: VariableOrder,
+}
+
+impl<F: Field> SparseMLE<F> {
+    // Repeated indices are summed
+    pub fn new(num_vars: usize, entries: impl IntoIterator<Item = (usize, F)>) -> Self {
+        Self::with_order(num_vars, entries, VariableOrder::LittleEndian)
+    }
+
+    pub fn with_order(
+        num_vars: usize,
+        entries: impl IntoIterator<Item = (usize, F)>,
+        order: VariableOrder,
+    ) -> Self {
+        let mut map = BTreeMap::new();
+        for (index, eval) in entries {
+            assert!(index < 1 << num_vars);
+            *map.entry(index).or_insert_with(F::zero) += eval;
+        }
+        map.retain(|_, eval| !eval.is_zero());
+        Self {
+            entries: map,
+            num_vars,
+            order,
+        }
+    }
+
+    pub fn entries(&self) -> &BTreeMap<usize, F> {
+        &self.entries
+    }
+
+    pub fn order(&self) -> VariableOrder {
+        self.order
+    }
+
+    pub fn to_order(&self, order: VariableOrder) -> Self {
+        let v = self.num_vars;
+        let reindex = |index: usize| {
+            if order == self.order {
+                index
+            } else {
+                reverse_bits(index, v)
+            }
+        };
+        let entries = self
+            .entries
+            .iter()
+            .map(|(index, eval)| (reindex(*index), *eval));
+        Self::with_order(v, entries, order)
+    }
+
+    pub fn to_dense(&self) -> DenseMLE<F> {
+        let mut evals = vec![F::zero(); 1 << self.num_vars];
+        for (index, eval) in &self.entries {
+            evals[*index] = *eval;
+        }
+        DenseMLE::with_order(evals, self.order)
+    }
+
+    // Whether x1 is set at `index`
+    fn first_bit(&self, index: usize) -> bool {
+        (index >> self.order.bit(0, self.num_vars)) & 1 == 1
+    }
+
+    // `index` with the bit of x1 removed
+    fn rest(&self, index: usize) -> usize {
+        match self.order {
+            VariableOrder::LittleEndian => index >> 1,
+            VariableOrder::BigEndian => index & ((1 << (self.num_vars - 1)) - 1),
+        }
+    }
+}
+
+impl<F: Field> SumCheckPolynomial<F> for SparseMLE<F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn degrees(&self) -> Vec<usize> {
+        vec![1; self.num_vars]
+    }
+
+    fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(point.len(), self.num_vars);
+        cfg_iter!(self.entries)
+            .map(|(index, eval)| {
+                *eval * eq_eval(point, &self.order.index_to_vars(*index, self.num_vars))
+            })
+            .sum()
+    }
+
+    // Each entry moves to the index of its remaining variables, weighted by r or 1 - r depending
+    // on its first bit
+    fn fix_first_variable(&self, r: F) -> Self {
+        let entries: Vec<(usize, F)> = cfg_iter!(self.entries)
+            .map(|(index, eval)| {
+                let weight = if self.first_bit(*index) {
+                    r
+                } else {
+                    F::one() - r
+                };
+                (self.rest(*index), weight * eval)
+            })
+            .collect();
+        Self::with_order(self.num_vars - 1, entries, self.order)
+    }
+
+    fn first_variable_evals(&self, num_points: usize) -> Vec<F> {
+        let sum_with = |bit: bool| -> F {
+            cfg_iter!(self.entries)
+                .filter(|(index, _)| self.first_bit(**index) == bit)
+                .map(|(_, eval)| *eval)
+                .sum()
+        };
+        let (sum_0, sum_1) = (sum_with(false), sum_with(true));
+        (0..num_points)
+            .map(|x| sum_0 + F::from(x as u64) * (sum_1 - sum_0))
+            .collect()
+    }
+
+    fn hypercube_sum(&self) -> F {
+        self.entries.values().sum()
+    }
+}
+
+// Shared between threads with the `parallel` feature
+type Combiner<F> = Arc<dyn Fn(&[F]) -> F + Send + Sync>;
+
+// The polynomial combine(f_1(x), ..., f_k(x)) for multilinear f_j given by their hypercube
+// evaluations, e.g. f(x) * g(x) * h(x). `degree` is the total degree of `combine`, which bounds
+// the degree of the combined polynomial in each variable.
+#[derive(Clone)]
+pub struct ProductMLE<F: Field> {
+    tables: Vec<Vec<F>>,
+    combine: Combiner<F>,
+    degree: usize,
+}
+
+impl<F: Field> ProductMLE<F> {
+    pub fn new(
+        tables: Vec<Vec<F>>,
+        combine: impl Fn(&[F]) -> F + Send + Sync + 'static,
+        degree: usize,
+    ) -> Self {
+        assert!(!tables.is_empty());
+        assert!(tables[0].len().is_power_of_two());
+        assert!(tables.iter().all(|table| table.len() == tables[0].len()));
+        Self {
+            tables,
+            combine: Arc::new(combine),
+            degree,
+        }
+    }
+
+    // Plain product of the factors
+    pub fn product(tables: Vec<Vec<F>>) -> Self {
+        let degree = tables.len();
+        Self::new(tables, |values| values.iter().product(), degree)
+    }
+
+    pub fn tables(&self) -> &[Vec<F>] {
+        &self.tables
+    }
+
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    // Applies `combine` to values of the factors, e.g. at a point or at a hypercube index
+    pub fn combine(&self, values: &[F]) -> F {
+        (self.combine)(values)
+    }
+
+    // eq(r, x) * self(x), with the hypercube evaluations of eq as an extra factor
+    pub fn times_eq(&self, r: &[F]) -> Self {
+        assert_eq!(r.len(), self.num_vars());
+        let combine = self.combine.clone();
+        Self {
+            tables: [vec![eq_evals(r)], self.tables.clone()].concat(),
+            combine: Arc::new(move |values: &[F]| values[0] * combine(&values[1..])),
+            degree: self.degree + 1,
+        }
+    }
+}
+
+impl<F: Field> SumCheckPolynomial<F> for ProductMLE<F> {
+    fn num_vars(&self) -> usize {
+        self.tables[0].len().trailing_zeros() as usize
+    }
+
+    fn degrees(&self) -> Vec<usize> {
+        vec![self.degree; self.num_vars()]
+    }
+
+    // One evaluation per factor
+    fn evaluate(&self, point: &[F]) -> F {
+        let values: Vec<F> = self
+            .tables
+            .iter()
+            .map(|table| dense_eval(point, table))
+            .collect();
+        (self.combine)(&values)
+    }
+
+    fn fix_first_variable(&self, r: F) -> Self {
+        Self {
+            tables: cfg_iter!(self.tables)
+                .map(|table| fix_first_variable(table, r))
+                .collect(),
+            combine: self.combine.clone(),
+            degree: self.degree,
+        }
+    }
+
+    fn first_variable_evals(&self, num_points: usize) -> Vec<F> {
+        let tables: Vec<&[F]> = self.tables.iter().map(|table| table.as_slice()).collect();
+        line_evals(&tables, self.num_vars(), num_points, |values| {
+            (self.combine)(values)
+        })
+    }
+
+    fn hypercube_sum(&self) -> F {
+        cfg_into_iter!(0..self.tables[0].len())
+            .map(|index| {
+                let values: Vec<F> = self.tables.iter().map(|table| table[index]).collect();
+                (self.combine)(&values)
+            })
+            .sum()
+    }
+}
+
+// Evaluations at x = 0, ..., num_points - 1 of the sum over the hypercube of the rest of
+// combine(f_1(x, ...), ..., f_k(x, ...)) for little-endian tables f_j of multilinear polynomials
+// in `num_vars` variables.
+// Each f_j is linear in the first variable, so its values at x = 0, 1, 2, ... along every line are
+// found by repeatedly adding f_j(1, ...) - f_j(0, ...). Takes O(k * num_points) field operations
+// per pair of table entries. With the `parallel` feature each thread sums its share of the lines
+// separately, and the partial sums are added up at the end.
+pub fn line_evals<F: Field>(
+    tables: &[&[F]],
+    num_vars: usize,
+    num_points: usize,
+    combine: impl Fn(&[F]) -> F + Sync,
+) -> Vec<F> {
+    // Partial sums, with scratch space for the values and steps of each factor along a line
+    type Acc<F> = (Vec<F>, Vec<F>, Vec<F>);
+    let k = tables.len();
+    let empty = || -> Acc<F> {
+        (
+            vec![F::zero(); num_points],
+            vec![F::zero(); k],
+            vec![F::zero(); k],
+        )
+    };
+    let add_line = |(mut evals, mut values, mut steps): Acc<F>, pair: usize| -> Acc<F> {
+        for (j, table) in tables.iter().enumerate() {
+            values[j] = table[2 * pair];
+            steps[j] = table[2 * pair + 1] - table[2 * pair];
+        }
+        for eval in evals.iter_mut() {
+            *eval += combine(&values);
+            for (value, step) in values.iter_mut().zip(&steps) {
+                *value += step;
+            }
+        }
+        (evals, values, steps)
+    };
+
+    let pairs = 0..1usize << (num_vars - 1);
+    #[cfg(feature = "parallel")]
+    let evals = pairs
+        .into_par_iter()
+        .fold(empty, add_line)
+        .map(|(evals, _, _)| evals)
+        .reduce(
+            || vec![F::zero(); num_points],
+            |mut lhs, rhs| {
+                lhs.iter_mut().zip(rhs).for_each(|(l, r)| *l += r);
+                lhs
+            },
+        );
+    #[cfg(not(feature = "parallel"))]
+    let evals = pairs.fold(empty(), add_line).0;
+    evals
+}
+
+type Stream<F> = Rc<dyn Fn() -> Box<dyn Iterator<Item = F>>>;
+
+// A multilinear polynomial whose hypercube evaluations are only available as a stream, e.g. read
+// from disk, that can be restarted but never held in memory. Fixing variables just records the
+// challenges, and every round makes one pass over the stream weighting each entry by the Lagrange
+// basis at those challenges, so the prover uses O(v) memory and O(v * 2^v) time per round.
+#[derive(Clone)]
+pub struct StreamingMLE<F: Field> {
+    stream: Stream<F>,
+    num_vars: usize,
+    fixed: Vec<F>,
+}
+
+impl<F: Field> StreamingMLE<F> {
+    // `stream` starts a new pass over the 2^num_vars evaluations, in hypercube index order
+    pub fn new<I>(num_vars: usize, stream: impl Fn() -> I + 'static) -> Self
+    where
+        I: Iterator<Item = F> + 'static,
+    {
+        Self {
+            stream: Rc::new(move || Box::new(stream())),
+            num_vars,
+            fixed: vec![],
+        }
+    }
+
+    // The entries of one pass, each with the weight of its fixed variables
+    fn weighted_pass(&self) -> impl Iterator<Item = (usize, F)> + '_ {
+        let k = self.fixed.len();
+        let mask = (1 << k) - 1;
+        (self.stream)().enumerate().map(move |(index, eval)| {
+            let weight = w_basis_eval(&self.fixed, (index & mask, eval), k);
+            (index >> k, weight * eval)
+        })
+    }
+}
+
+impl<F: Field> SumCheckPolynomial<F> for StreamingMLE<F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars - self.fixed.len()
+    }
+
+    fn degrees(&self) -> Vec<usize> {
+        vec![1; self.num_vars()]
+    }
+
+    fn evaluate(&self, point: &[F]) -> F {
+        let v = self.num_vars();
+        assert_eq!(point.len(), v);
+        self.weighted_pass()
+            .map(|(rest, eval)| eval * w_basis_eval(point, (rest, eval), v))
+            .sum()
+    }
+
+    fn fix_first_variable(&self, r: F) -> Self {
+        Self {
+            stream: self.stream.clone(),
+            num_vars: self.num_vars,
+            fixed: [self.fixed.as_slice(), &[r]].concat(),
+        }
+    }
+
+    fn first_variable_evals(&self, num_points: usize) -> Vec<F> {
+        let (sum_0, sum_1) =
+            self.weighted_pass()
+                .fold((F::zero(), F::zero()), |(sum_0, sum_1), (rest, eval)| {
+                    if rest & 1 == 0 {
+                        (sum_0 + eval, sum_1)
+                    } else {
+                        (sum_0, sum_1 + eval)
+                    }
+                });
+        (0..num_points)
+            .map(|x| sum_0 + F::from(x as u64) * (sum_1 - sum_0))
+            .collect()
+    }
+
+    fn hypercube_sum(&self) -> F {
+        self.weighted_pass().map(|(_, eval)| eval).sum()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::fields::Fr;
+    use crate::{
+        fields::{random_elem, random_vec, Fr},
+        ip::{Driver, Status},
+        polynomials::OraclePolynomial,
+        sumcheck::SumCheck,
+    };
+    use ark_poly::MultilinearExtension;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
 
     #[test]
     fn test_partial_sum() {
@@ -97,12 +738,32 @@ mod tests {
             Fr::from(2),
             Fr::from(4),
         ];
-        let univariate_evals = calculate_g_i(&[], &evals, v);
+        let univariate_evals = calculate_g_i(&[], &evals, v, 2);
 
         // Sum g(0) + g(1) should be 8
         assert_eq!(univariate_evals[0] + univariate_evals[1], Fr::from(16));
     }
 
+    #[test]
+    fn test_bookkeeping_table() {
+        let v = 4;
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let evals = random_evals::<Fr, _>(v, &mut rng);
+
+        let mut table = evals.clone();
+        let mut randoms = vec![];
+        for _ in 0..v {
+            assert_eq!(
+                first_variable_evals(&table, 3),
+                calculate_g_i(&randoms, &evals, v, 3)
+            );
+            let r = random_elem::<Fr, _>(&mut rng);
+            table = fix_first_variable(&table, r);
+            randoms.push(r);
+        }
+        assert_eq!(table, vec![stream_eval(&randoms, &evals, v)]);
+    }
+
     #[test]
     fn test_g_sum() {
         let mut sum = Fr::from(0);
@@ -127,4 +788,227 @@ mod tests {
             println!();
         }
     }
+
+    #[test]
+    fn test_eq() {
+        let v = 3;
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let r = random_vec::<Fr, _>(v, &mut rng);
+        let x = random_vec::<Fr, _>(v, &mut rng);
+        let table = eq_evals(&r);
+        assert_eq!(stream_eval(&x, &table, v), eq_eval(&r, &x));
+        for (index, eval) in table.iter().enumerate() {
+            assert_eq!(*eval, eq_eval(&r, &index_to_vars(index, v)));
+        }
+    }
+
+    #[test]
+    fn test_product_mle() {
+        let v = 3;
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let tables: Vec<Vec<Fr>> = (0..3).map(|_| random_evals(v, &mut rng)).collect();
+        let product = ProductMLE::product(tables.clone());
+        assert_eq!(product.degrees(), vec![3; v]);
+
+        // Matches querying the product pointwise
+        let oracle = OraclePolynomial::new(
+            move |point: &[Fr]| tables.iter().map(|t| stream_eval(point, t, v)).product(),
+            vec![3; v],
+        );
+        assert_eq!(product.hypercube_sum(), oracle.hypercube_sum());
+        assert_eq!(
+            product.first_variable_evals(4),
+            oracle.first_variable_evals(4)
+        );
+        let r = random_elem::<Fr, _>(&mut rng);
+        assert_eq!(
+            product.fix_first_variable(r).first_variable_evals(4),
+            oracle.fix_first_variable(r).first_variable_evals(4)
+        );
+    }
+
+    #[test]
+    fn test_streaming_mle() {
+        let v = 4;
+        // Regenerates the same evaluations on every pass instead of storing them
+        let stream = StreamingMLE::new(v, move || {
+            let mut rng = ChaCha20Rng::seed_from_u64(7);
+            (0..1 << v).map(move |_| random_elem::<Fr, _>(&mut rng))
+        });
+        let mut table = random_evals::<Fr, _>(v, &mut ChaCha20Rng::seed_from_u64(7));
+        assert_eq!(stream.hypercube_sum(), table.hypercube_sum());
+
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let mut stream = stream;
+        for _ in 0..v {
+            assert_eq!(
+                stream.first_variable_evals(3),
+                table.first_variable_evals(3)
+            );
+            let point = random_vec::<Fr, _>(stream.num_vars(), &mut rng);
+            assert_eq!(stream.evaluate(&point), table.evaluate(&point));
+
+            let r = random_elem::<Fr, _>(&mut rng);
+            stream = stream.fix_first_variable(r);
+            table = fix_first_variable(&table, r);
+        }
+    }
+
+    #[test]
+    fn test_dense_mle() {
+        let v = 4;
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let evals = random_evals::<Fr, _>(v, &mut rng);
+        let mle = DenseMLE::new(evals.clone());
+        let point = random_vec::<Fr, _>(v, &mut rng);
+        assert_eq!(mle.evaluate(&point), stream_eval(&point, &evals, v));
+
+        let ark_mle = DenseMultilinearExtension::from(mle.clone());
+        assert_eq!(
+            MultilinearExtension::evaluate(&ark_mle, &point),
+            Some(mle.evaluate(&point))
+        );
+        assert_eq!(
+            DenseMLE::from(ark_mle.fix_variables(&point[..1])),
+            mle.fix_variable(point[0])
+        );
+        assert_eq!(
+            mle.fix_variable(point[0]).evaluate(&point[1..]),
+            mle.evaluate(&point)
+        );
+    }
+
+    #[test]
+    fn test_sparse_mle() {
+        let v = 10;
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let mut sparse = SparseMLE::new(
+            v,
+            [
+                (3, Fr::from(1)),
+                (700, Fr::from(2)),
+                (3, Fr::from(4)),
+                (1023, Fr::from(3)),
+            ],
+        );
+        // The two entries at 3 cancel out
+        assert_eq!(sparse.entries().len(), 2);
+        let mut dense = sparse.to_dense();
+
+        for _ in 0..v {
+            let point = random_vec::<Fr, _>(sparse.num_vars(), &mut rng);
+            assert_eq!(
+                SumCheckPolynomial::evaluate(&sparse, &point),
+                dense.evaluate(&point)
+            );
+            assert_eq!(
+                sparse.first_variable_evals(2),
+                dense.first_variable_evals(2)
+            );
+
+            let r = random_elem::<Fr, _>(&mut rng);
+            sparse = sparse.fix_first_variable(r);
+            dense = dense.fix_variable(r);
+            assert_eq!(sparse.to_dense(), dense);
+        }
+    }
+
+    #[test]
+    fn test_variable_order() {
+        let v = 4;
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let evals = random_evals::<Fr, _>(v, &mut rng);
+        let little = DenseMLE::new(evals.clone());
+        let big = little.to_order(VariableOrder::BigEndian);
+        assert_eq!(big.to_order(VariableOrder::LittleEndian), little);
+        // x1 = 1, x2 = x3 = x4 = 0 is index 1 in little-endian and 8 in big-endian
+        assert_eq!(big.evals()[8], little.evals()[1]);
+        for index in 0..1 << v {
+            assert_eq!(
+                VariableOrder::BigEndian.index_to_vars::<Fr>(reverse_bits(index, v), v),
+                index_to_vars::<Fr>(index, v)
+            );
+        }
+
+        let point = random_vec::<Fr, _>(v, &mut rng);
+        assert_eq!(big.evaluate(&point), little.evaluate(&point));
+        assert_eq!(
+            MultilinearExtension::evaluate(&DenseMultilinearExtension::from(big.clone()), &point),
+            Some(little.evaluate(&point))
+        );
+        assert_eq!(big.first_variable_evals(3), little.first_variable_evals(3));
+        assert_eq!(
+            big.fix_variable(point[0])
+                .to_order(VariableOrder::LittleEndian),
+            little.fix_variable(point[0])
+        );
+
+        let sparse = SparseMLE::new(v, [(1, Fr::from(2)), (6, Fr::from(3))]);
+        let sparse_big = sparse.to_order(VariableOrder::BigEndian);
+        assert_eq!(
+            sparse_big.to_dense(),
+            sparse.to_dense().to_order(VariableOrder::BigEndian)
+        );
+        assert_eq!(
+            SumCheckPolynomial::evaluate(&sparse_big, &point),
+            SumCheckPolynomial::evaluate(&sparse, &point)
+        );
+        assert_eq!(
+            sparse_big
+                .fix_first_variable(point[0])
+                .to_order(VariableOrder::LittleEndian),
+            sparse.fix_first_variable(point[0])
+        );
+    }
+
+    #[test]
+    fn test_dense_mle_constructors() {
+        // x1 AND NOT x3
+        let mle = DenseMLE::<Fr>::from_fn(3, |x| bool_to_field(x[0] && !x[2]));
+        assert_eq!(mle.evals()[0b001], Fr::from(1));
+        assert_eq!(mle.evals()[0b011], Fr::from(1));
+        assert_eq!(mle.evals()[0b101], Fr::from(0));
+        assert_eq!(mle.hypercube_sum(), Fr::from(2));
+
+        let bytes = DenseMLE::<Fr>::from_bytes(&[7, 1, 3]);
+        assert_eq!(
+            bytes.evals(),
+            &[Fr::from(2), Fr::from(1), Fr::from(3), Fr::from(0)]
+        );
+    }
+
+    #[test]
+    fn test_matrix_mle() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let a = Array2::from_shape_fn((3, 4), |_| random_elem::<Fr, _>(&mut rng));
+        let mle = DenseMLE::from_matrix(&a);
+        assert_eq!(matrix_num_vars(&a), (2, 2));
+        for ((i, j), entry) in a.indexed_iter() {
+            let point = [index_to_vars::<Fr>(i, 2), index_to_vars(j, 2)].concat();
+            assert_eq!(mle.evaluate(&point), *entry);
+        }
+        // Padding row
+        let point = [index_to_vars::<Fr>(3, 2), index_to_vars(1, 2)].concat();
+        assert_eq!(mle.evaluate(&point), Fr::from(0));
+    }
+
+    #[test]
+    fn test_matmul_sumcheck_claim() {
+        // f_C(r1, r2) = sum_k f_A(r1, k) * f_B(k, r2) over k in the hypercube
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let n = 4;
+        let a = Array2::from_shape_fn((n, n), |_| random_elem::<Fr, _>(&mut rng));
+        let b = Array2::from_shape_fn((n, n), |_| random_elem::<Fr, _>(&mut rng));
+        let (r1, r2) = (random_vec::<Fr, _>(2, &mut rng), random_vec(2, &mut rng));
+
+        let fix = |mle: DenseMLE<Fr>, r: &[Fr]| r.iter().fold(mle, |mle, r| mle.fix_variable(*r));
+        let a_row = fix(DenseMLE::from_matrix(&a), &r1);
+        let b_col = fix(DenseMLE::from_matrix(&b.t().to_owned()), &r2);
+        let product = ProductMLE::product(vec![a_row.evals().to_vec(), b_col.evals().to_vec()]);
+
+        let c = DenseMLE::from_matrix(&a.dot(&b));
+        assert_eq!(product.hypercube_sum(), c.evaluate(&[r1, r2].concat()));
+        let mut ip = Driver::<SumCheck<Fr, ProductMLE<Fr>>>::new(product);
+        assert_eq!(ip.run(), Status::Accepted);
+    }
 }
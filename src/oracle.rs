@@ -0,0 +1,340 @@
+use crate::{mle::stream_eval, sumcheck::SumCheckPolynomial};
+use ark_ff::Field;
+use ark_serialize::{CanonicalSerialize, Compress, SerializationError};
+use ark_std::{io::Write, marker::PhantomData, rc::Rc};
+use sha2::{Digest, Sha256};
+
+// Access to a polynomial for the verifier's final check, which queries it at a single random
+// point. Every query is counted, so that tests can confirm the verifier only makes one.
+pub trait PolynomialOracle<F: Field> {
+    fn num_vars(&self) -> usize;
+
+    // Bound on the degree in each variable, which the verifier checks the prover's messages against
+    fn degrees(&self) -> Vec<usize>;
+
+    // Number of points {0, ..., m - 1} each variable is summed over, 2 for the hypercube
+    fn domain_size(&self) -> usize {
+        2
+    }
+
+    // The polynomial's value at `point`, or None if the answer fails to verify
+    fn query(&mut self, point: &[F]) -> Option<F>;
+
+    fn num_queries(&self) -> usize;
+}
+
+// The verifier has the whole polynomial and evaluates it itself, e.g. in O(v * 2^v) for the
+// hypercube evaluations of a multilinear polynomial. Sound, but not succinct.
+#[derive(Debug, Clone)]
+pub struct SelfEvaluation<F: Field, P: SumCheckPolynomial<F>> {
+    poly: P,
+    queries: usize,
+    _field: PhantomData<F>,
+}
+
+impl<F: Field, P: SumCheckPolynomial<F>> From<P> for SelfEvaluation<F, P> {
+    fn from(poly: P) -> Self {
+        Self {
+            poly,
+            queries: 0,
+            _field: PhantomData,
+        }
+    }
+}
+
+impl<F: Field, P: SumCheckPolynomial<F>> PolynomialOracle<F> for SelfEvaluation<F, P> {
+    fn num_vars(&self) -> usize {
+        self.poly.num_vars()
+    }
+
+    fn degrees(&self) -> Vec<usize> {
+        self.poly.degrees()
+    }
+
+    fn domain_size(&self) -> usize {
+        self.poly.domain_size()
+    }
+
+    fn query(&mut self, point: &[F]) -> Option<F> {
+        self.queries += 1;
+        Some(self.poly.evaluate(point))
+    }
+
+    fn num_queries(&self) -> usize {
+        self.queries
+    }
+}
+
+// The polynomial is part of the statement, so only it is absorbed into Fiat-Shamir
+impl<F: Field, P: SumCheckPolynomial<F> + CanonicalSerialize> CanonicalSerialize
+    for SelfEvaluation<F, P>
+{
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.poly.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.poly.serialized_size(compress)
+    }
+}
+
+// Answers a query with the polynomial's value at a point
+pub type Answer<F> = Rc<dyn Fn(&[F]) -> F>;
+
+// Evaluations come from a third party the verifier trusts, e.g. a polynomial that is a public
+// function of the input which someone else has already evaluated. The verifier only holds the
+// source of answers along with the shape of the polynomial, and takes the answers as they are.
+#[derive(Clone)]
+pub struct TrustedOracle<F: Field> {
+    num_vars: usize,
+    degrees: Vec<usize>,
+    answer: Answer<F>,
+    queries: usize,
+}
+
+impl<F: Field> TrustedOracle<F> {
+    // `degrees[i]` bounds the degree in variable i, which is summed over the hypercube
+    pub fn new(degrees: Vec<usize>, answer: Answer<F>) -> Self {
+        Self {
+            num_vars: degrees.len(),
+            degrees,
+            answer,
+            queries: 0,
+        }
+    }
+}
+
+// A third party that evaluates the multilinear extension of the table honestly
+impl<F: Field> From<Vec<F>> for TrustedOracle<F> {
+    fn from(evals: Vec<F>) -> Self {
+        let degrees = evals.degrees();
+        Self::new(degrees, Rc::new(move |point: &[F]| evals.evaluate(point)))
+    }
+}
+
+impl<F: Field> PolynomialOracle<F> for TrustedOracle<F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn degrees(&self) -> Vec<usize> {
+        self.degrees.clone()
+    }
+
+    fn query(&mut self, point: &[F]) -> Option<F> {
+        self.queries += 1;
+        Some((self.answer)(point))
+    }
+
+    fn num_queries(&self) -> usize {
+        self.queries
+    }
+}
+
+// SHA-256 of the serialized hypercube evaluations
+pub fn commit<F: Field>(evals: &[F]) -> [u8; 32] {
+    let mut bytes = vec![];
+    evals
+        .serialize_compressed(&mut bytes)
+        .expect("serializing into a Vec cannot fail");
+    Sha256::digest(bytes).into()
+}
+
+// Answers to a query: the claimed value, and an opening proving it against the commitment
+pub type Opener<F> = Rc<dyn Fn(&[F]) -> (F, Vec<F>)>;
+
+// The verifier only holds a commitment to a multilinear polynomial, and the prover answers the
+// query with the value and an opening, which the verifier checks against the commitment. The
+// opening here is the whole table, standing in for the proof of a real polynomial commitment
+// scheme, so checking it is no cheaper than `SelfEvaluation`.
+#[derive(Clone)]
+pub struct CommitmentOpening<F: Field> {
+    commitment: [u8; 32],
+    num_vars: usize,
+    opener: Opener<F>,
+    queries: usize,
+}
+
+impl<F: Field> CommitmentOpening<F> {
+    pub fn new(commitment: [u8; 32], num_vars: usize, opener: Opener<F>) -> Self {
+        Self {
+            commitment,
+            num_vars,
+            opener,
+            queries: 0,
+        }
+    }
+}
+
+// Commits to the evaluations, with a prover that opens them honestly
+impl<F: Field> From<Vec<F>> for CommitmentOpening<F> {
+    fn from(evals: Vec<F>) -> Self {
+        let num_vars = SumCheckPolynomial::num_vars(&evals);
+        let commitment = commit(&evals);
+        let opener = Rc::new(move |point: &[F]| (evals.evaluate(point), evals.clone()));
+        Self::new(commitment, num_vars, opener)
+    }
+}
+
+impl<F: Field> PolynomialOracle<F> for CommitmentOpening<F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn degrees(&self) -> Vec<usize> {
+        vec![1; self.num_vars]
+    }
+
+    fn query(&mut self, point: &[F]) -> Option<F> {
+        self.queries += 1;
+        let (value, opening) = (self.opener)(point);
+        let valid = opening.len() == 1 << self.num_vars
+            && commit(&opening) == self.commitment
+            && stream_eval(point, &opening, self.num_vars) == value;
+        valid.then_some(value)
+    }
+
+    fn num_queries(&self) -> usize {
+        self.queries
+    }
+}
+
+impl<F: Field> CanonicalSerialize for CommitmentOpening<F> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.commitment.serialize_with_mode(&mut writer, compress)?;
+        self.num_vars.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.commitment.serialized_size(compress) + self.num_vars.serialized_size(compress)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        adversary::{Honest, LieInRound},
+        fields::Fr,
+        ip::*,
+        mle::random_evals,
+        sumcheck::{SumCheck, SumCheckProver, SumCheckVerifier},
+    };
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    type CommittedSumCheck = SumCheck<Fr, Vec<Fr>, CommitmentOpening<Fr>>;
+
+    #[test]
+    fn test_single_query() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let evals = random_evals::<Fr, _>(4, &mut rng);
+
+        let mut ip = Driver::<SumCheck<Fr>>::with_seed(evals.clone(), Honest, 0);
+        assert_eq!(ip.run(), Status::Accepted);
+        assert_eq!(ip.verifier.oracle().num_queries(), 1);
+
+        let mut ip =
+            Driver::<SumCheck<Fr, Vec<Fr>, TrustedOracle<Fr>>>::with_seed(evals.clone(), Honest, 0);
+        assert_eq!(ip.run(), Status::Accepted);
+        assert_eq!(ip.verifier.oracle().num_queries(), 1);
+
+        let mut ip = Driver::<CommittedSumCheck>::with_seed(evals, Honest, 0);
+        assert_eq!(ip.run(), Status::Accepted);
+        assert_eq!(ip.verifier.oracle().num_queries(), 1);
+    }
+
+    #[test]
+    fn test_lie_caught_by_query() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let evals = random_evals::<Fr, _>(4, &mut rng);
+        let rejected = (0..20)
+            .filter(|&seed| {
+                let mut ip = Driver::<CommittedSumCheck, _>::with_seed(
+                    evals.clone(),
+                    LieInRound { round: 0 },
+                    seed,
+                );
+                ip.log_rejections = false;
+                let status = ip.run();
+                assert_eq!(ip.verifier.oracle().num_queries(), 1);
+                status == Status::Rejected
+            })
+            .count();
+        assert!(rejected > 0);
+    }
+
+    #[test]
+    fn test_bad_openings() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let evals = random_evals::<Fr, _>(3, &mut rng);
+        let point = vec![Fr::from(2), Fr::from(3), Fr::from(4)];
+        let value = evals.evaluate(&point);
+
+        let mut honest = CommitmentOpening::from(evals.clone());
+        assert_eq!(honest.query(&point), Some(value));
+
+        // Wrong value for the committed table
+        let table = evals.clone();
+        let mut lying = CommitmentOpening::new(
+            commit(&evals),
+            3,
+            Rc::new(move |_: &[Fr]| (value + Fr::from(1), table.clone())),
+        );
+        assert_eq!(lying.query(&point), None);
+
+        // Consistent value, but for a different table than the one committed to
+        let mut other = evals.clone();
+        other[0] += Fr::from(1);
+        let mut swapped = CommitmentOpening::new(
+            commit(&evals),
+            3,
+            Rc::new(move |point: &[Fr]| (other.evaluate(point), other.clone())),
+        );
+        assert_eq!(swapped.query(&point), None);
+        assert_eq!(swapped.num_queries(), 1);
+    }
+
+    #[test]
+    fn test_trusted_oracle() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let evals = random_evals::<Fr, _>(3, &mut rng);
+        let sum = evals.hypercube_sum();
+
+        // The verifier gets the claim and a source of answers, never the table itself
+        let table = evals.clone();
+        let oracle = TrustedOracle::new(
+            vec![1; 3],
+            Rc::new(move |point: &[Fr]| table.evaluate(point)),
+        );
+        let verifier = SumCheckVerifier::initialize((sum, oracle));
+        let prover = SumCheckProver::new(evals.clone());
+        let mut ip = Driver::<SumCheck<Fr, Vec<Fr>, TrustedOracle<Fr>>>::from_parts(
+            prover, verifier, Honest, 0,
+        );
+        assert_eq!(ip.run(), Status::Accepted);
+
+        // Answers are taken as they are, so a wrong one fails the final check
+        let table = evals.clone();
+        let answer = move |point: &[Fr]| table.evaluate(point) + Fr::from(1);
+        let verifier =
+            SumCheckVerifier::initialize((sum, TrustedOracle::new(vec![1; 3], Rc::new(answer))));
+        let mut ip = Driver::<SumCheck<Fr, Vec<Fr>, TrustedOracle<Fr>>>::from_parts(
+            SumCheckProver::new(evals),
+            verifier,
+            Honest,
+            0,
+        );
+        ip.log_rejections = false;
+        assert_eq!(ip.run(), Status::Rejected);
+        assert_eq!(ip.verifier.oracle().num_queries(), 1);
+    }
+}
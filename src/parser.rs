@@ -0,0 +1,326 @@
+use crate::fields::distinct_points;
+use ark_ff::Field;
+use ark_poly::{
+    polynomial::multivariate::{SparsePolynomial, SparseTerm, Term},
+    DenseMVPolynomial,
+};
+use ark_std::{fmt, iter::Peekable, str::CharIndices};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// The input has no terms
+    Empty,
+    /// `found` at byte `position` cannot start or continue a term
+    UnexpectedChar { position: usize, found: char },
+    /// The input ends in the middle of a term
+    UnexpectedEnd,
+    /// The number at byte `position` does not fit in a u64
+    NumberTooLarge { position: usize },
+    /// The variable at byte `position` is x0, but variables are numbered from x1
+    ZeroVariable { position: usize },
+    /// x`found` appears in a polynomial declared to have `num_vars` variables, or past `MAX_VARS`
+    TooManyVariables { found: usize, num_vars: usize },
+    /// The variable at byte `position` reaches degree `degree` in its monomial, which needs more
+    /// evaluation points than the field has to be sumchecked
+    DegreeTooLarge { position: usize, degree: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "empty polynomial"),
+            ParseError::UnexpectedChar { position, found } => {
+                write!(f, "unexpected '{}' at position {}", found, position)
+            }
+            ParseError::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ParseError::NumberTooLarge { position } => {
+                write!(f, "number at position {} does not fit in a u64", position)
+            }
+            ParseError::ZeroVariable { position } => {
+                write!(f, "x0 at position {}, variables start at x1", position)
+            }
+            ParseError::TooManyVariables { found, num_vars } => {
+                write!(f, "x{} in a polynomial in {} variables", found, num_vars)
+            }
+            ParseError::DegreeTooLarge { position, degree } => {
+                write!(
+                    f,
+                    "degree {} at position {} is too large for the field",
+                    degree, position
+                )
+            }
+        }
+    }
+}
+
+// Sumcheck sums over 2^v points, so more variables than this could never be run
+pub const MAX_VARS: usize = 64;
+
+// Parses a sum of monomials such as "2*x1^3 + x1*x3 - x2*x3" into a polynomial in as many
+// variables as the highest one named. Each monomial is a product of integer constants and powers
+// of variables x1, x2, ... (or X1, X2, ...), separated by `*`. Parentheses are not supported.
+// Like the rest of the crate, x1 is `ark_poly`'s variable 0 and the first to be fixed by sumcheck,
+// whose degree bounds come from `SumCheckPolynomial::degrees`. Variables past `MAX_VARS`, and
+// powers too high for the messages of sumcheck to be interpolated in F, are errors.
+pub fn parse_polynomial<F: Field>(
+    input: &str,
+) -> Result<SparsePolynomial<F, SparseTerm>, ParseError> {
+    let terms = Parser::new(input).polynomial()?;
+    let num_vars = terms
+        .iter()
+        .flat_map(|(_, term)| term.iter().map(|(var, _)| var + 1))
+        .max()
+        .unwrap_or(0);
+    Ok(to_polynomial(num_vars, terms))
+}
+
+// As `parse_polynomial`, for a polynomial in `num_vars` variables, some of which may not appear
+pub fn parse_polynomial_with_vars<F: Field>(
+    input: &str,
+    num_vars: usize,
+) -> Result<SparsePolynomial<F, SparseTerm>, ParseError> {
+    let terms = Parser::new(input).polynomial()?;
+    for (_, term) in &terms {
+        if let Some((var, _)) = term.iter().find(|(var, _)| *var >= num_vars) {
+            return Err(ParseError::TooManyVariables {
+                found: var + 1,
+                num_vars,
+            });
+        }
+    }
+    Ok(to_polynomial(num_vars, terms))
+}
+
+type Monomial<F> = (F, Vec<(usize, usize)>);
+
+fn to_polynomial<F: Field>(
+    num_vars: usize,
+    terms: Vec<Monomial<F>>,
+) -> SparsePolynomial<F, SparseTerm> {
+    let terms = terms
+        .into_iter()
+        .map(|(coeff, term)| (coeff, SparseTerm::new(term)))
+        .collect();
+    // Combines like terms and drops those that cancel
+    SparsePolynomial::from_coefficients_vec(num_vars, terms)
+}
+
+struct Parser<'a> {
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            chars: input.char_indices().peekable(),
+        }
+    }
+
+    // Next character that is not whitespace, without consuming it
+    fn peek(&mut self) -> Option<(usize, char)> {
+        while let Some((_, c)) = self.chars.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.chars.next();
+        }
+        self.chars.peek().copied()
+    }
+
+    fn polynomial<F: Field>(&mut self) -> Result<Vec<Monomial<F>>, ParseError> {
+        if self.peek().is_none() {
+            return Err(ParseError::Empty);
+        }
+        let mut terms = vec![];
+        let mut negate = false;
+        if let Some((_, '-')) = self.peek() {
+            self.chars.next();
+            negate = true;
+        }
+        loop {
+            let (coeff, term) = self.monomial::<F>()?;
+            terms.push((if negate { -coeff } else { coeff }, term));
+            match self.peek() {
+                None => return Ok(terms),
+                Some((_, '+')) => negate = false,
+                Some((_, '-')) => negate = true,
+                Some((position, found)) => {
+                    return Err(ParseError::UnexpectedChar { position, found })
+                }
+            }
+            self.chars.next();
+        }
+    }
+
+    fn monomial<F: Field>(&mut self) -> Result<Monomial<F>, ParseError> {
+        let mut coeff = F::one();
+        let mut term = vec![];
+        loop {
+            match self.peek() {
+                None => return Err(ParseError::UnexpectedEnd),
+                Some((_, c)) if c.is_ascii_digit() => coeff *= F::from(self.number()?),
+                Some((position, 'x' | 'X')) => {
+                    self.chars.next();
+                    let var = self.number()? as usize;
+                    if var == 0 {
+                        return Err(ParseError::ZeroVariable { position });
+                    }
+                    if var > MAX_VARS {
+                        return Err(ParseError::TooManyVariables {
+                            found: var,
+                            num_vars: MAX_VARS,
+                        });
+                    }
+                    let mut power = 1;
+                    if let Some((_, '^')) = self.peek() {
+                        self.chars.next();
+                        power = self.number()? as usize;
+                    }
+                    // Powers of the same variable multiply, as `SparseTerm` combines them
+                    let degree = term
+                        .iter()
+                        .filter(|(v, _)| *v == var - 1)
+                        .fold(power, |degree: usize, (_, p)| degree.saturating_add(*p));
+                    if !distinct_points::<F>(degree.saturating_add(1)) {
+                        return Err(ParseError::DegreeTooLarge { position, degree });
+                    }
+                    if power > 0 {
+                        term.push((var - 1, power));
+                    }
+                }
+                Some((position, found)) => {
+                    return Err(ParseError::UnexpectedChar { position, found })
+                }
+            }
+            match self.peek() {
+                Some((_, '*')) => {
+                    self.chars.next();
+                }
+                _ => return Ok((coeff, term)),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<u64, ParseError> {
+        let (position, _) = self.peek().ok_or(ParseError::UnexpectedEnd)?;
+        let mut digits = String::new();
+        while let Some((_, c)) = self.chars.peek() {
+            if !c.is_ascii_digit() {
+                break;
+            }
+            digits.push(*c);
+            self.chars.next();
+        }
+        if digits.is_empty() {
+            let (position, found) = self.peek().ok_or(ParseError::UnexpectedEnd)?;
+            return Err(ParseError::UnexpectedChar { position, found });
+        }
+        digits
+            .parse()
+            .map_err(|_| ParseError::NumberTooLarge { position })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fields::{random_vec, Fr},
+        ip::{Driver, Status},
+        mle::g_poly,
+        sumcheck::{SumCheck, SumCheckPolynomial},
+    };
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_parse_book_example() {
+        let poly = parse_polynomial::<Fr>("2*x1^3 + x1*x3 + x2*x3").unwrap();
+        assert_eq!(poly.degrees(), vec![3, 1, 1]);
+
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        for _ in 0..10 {
+            let point = random_vec::<Fr, _>(3, &mut rng);
+            assert_eq!(SumCheckPolynomial::evaluate(&poly, &point), g_poly(&point));
+        }
+
+        let mut ip = Driver::<SumCheck<Fr, SparsePolynomial<Fr, SparseTerm>>>::new(poly);
+        assert_eq!(ip.run(), Status::Accepted);
+    }
+
+    #[test]
+    fn test_parse_syntax() {
+        // Whitespace, case, constants, subtraction and like terms
+        let poly = parse_polynomial::<Fr>(" -X2 *x2+3 - 2 * 2*x1 + x2^2*4 ").unwrap();
+        let expected = parse_polynomial::<Fr>("3*x2^2 + 3 + 1*x1").unwrap();
+        assert_eq!(poly, expected);
+
+        // Cancelling terms leave the variables they named
+        let poly = parse_polynomial_with_vars::<Fr>("x3 - x3 + x1^0", 4).unwrap();
+        assert_eq!(poly.degrees(), vec![0, 0, 0, 0]);
+        assert_eq!(poly.hypercube_sum(), Fr::from(16));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |input| parse_polynomial::<Fr>(input).unwrap_err();
+        assert_eq!(parse("  "), ParseError::Empty);
+        assert_eq!(parse("x1 +"), ParseError::UnexpectedEnd);
+        assert_eq!(
+            parse("x1 * y2"),
+            ParseError::UnexpectedChar {
+                position: 5,
+                found: 'y'
+            }
+        );
+        assert_eq!(
+            parse("x1 x2"),
+            ParseError::UnexpectedChar {
+                position: 3,
+                found: 'x'
+            }
+        );
+        assert_eq!(
+            parse("xa"),
+            ParseError::UnexpectedChar {
+                position: 1,
+                found: 'a'
+            }
+        );
+        assert_eq!(parse("2 + x0"), ParseError::ZeroVariable { position: 4 });
+        assert_eq!(
+            parse("99999999999999999999 * x1"),
+            ParseError::NumberTooLarge { position: 0 }
+        );
+        assert_eq!(
+            parse("x100000000000"),
+            ParseError::TooManyVariables {
+                found: 100000000000,
+                num_vars: MAX_VARS
+            }
+        );
+        // Degree 5 needs 6 distinct points, and F_5 has 5
+        assert_eq!(
+            parse("x1^5 + x2"),
+            ParseError::DegreeTooLarge {
+                position: 0,
+                degree: 5
+            }
+        );
+        assert_eq!(
+            parse("x2 + x1^3 * x2 * x1^2"),
+            ParseError::DegreeTooLarge {
+                position: 17,
+                degree: 5
+            }
+        );
+        assert!(parse_polynomial::<Fr>("x1^4 * x2^4").is_ok());
+        assert_eq!(
+            parse_polynomial_with_vars::<Fr>("x1 + x3", 2).unwrap_err(),
+            ParseError::TooManyVariables {
+                found: 3,
+                num_vars: 2
+            }
+        );
+    }
+}
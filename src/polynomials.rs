@@ -1,30 +1,388 @@
-use ark_ff::{FftField, Field};
-use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use crate::{fields::distinct_points, sumcheck::SumCheckPolynomial};
+use ark_ff::{batch_inversion, FftField, Field};
+use ark_poly::{
+    polynomial::multivariate::{SparsePolynomial, SparseTerm, Term},
+    univariate::DensePolynomial,
+    DenseMVPolynomial, DenseUVPolynomial, EvaluationDomain, Evaluations, Polynomial,
+    Radix2EvaluationDomain,
+};
+use ark_std::rc::Rc;
 
-// Silly helper function that index an array of full field evaluations by a random point
-pub fn eval_poly<F: FftField>(evals: &[F], r: F) -> F {
-    let x_values = vec![F::zero(), F::one()];
-    let poly = interpolate_degree_1(&x_values, &evals);
+// Evaluates at r the unique polynomial of degree < evals.len() taking the value evals[i] at
+// x = i. Takes O(n) field operations for n evaluations (see `Interpolator`).
+pub fn eval_poly<F: Field>(evals: &[F], r: F) -> F {
+    if evals.is_empty() {
+        return F::zero();
+    }
+    Interpolator::new(evals.len() - 1).evaluate(evals, r)
+}
+
+// Evaluates at r the unique polynomial of degree < xs.len() through the points (xs[i], ys[i]),
+// by Lagrange interpolation. Takes O(n^2) field operations for n points, which have to be
+// distinct.
+pub fn lagrange_eval<F: Field>(xs: &[F], ys: &[F], r: F) -> F {
+    assert_eq!(xs.len(), ys.len());
+    let mut res = F::zero();
+    for (i, (x_i, y_i)) in xs.iter().zip(ys).enumerate() {
+        let mut numerator = F::one();
+        let mut denominator = F::one();
+        for (j, x_j) in xs.iter().enumerate() {
+            if i != j {
+                numerator *= r - x_j;
+                denominator *= *x_i - x_j;
+            }
+        }
+        res += *y_i * numerator * denominator.inverse().unwrap();
+    }
+    res
+}
+
+// Coefficients of the polynomial through the points (xs[i], ys[i]), as sum_i ys[i] * w_i * M(X) /
+// (X - xs[i]) for M(X) = prod_i (X - xs[i]) and the barycentric weights w_i. Takes O(n^2).
+pub fn lagrange_interpolate<F: Field>(xs: &[F], ys: &[F]) -> DensePolynomial<F> {
+    assert_eq!(xs.len(), ys.len());
+    let mut master = vec![F::one()];
+    for x in xs {
+        // Multiply by (X - x)
+        master.insert(0, F::zero());
+        for k in 0..master.len() - 1 {
+            let next = master[k + 1];
+            master[k] -= *x * next;
+        }
+    }
+
+    let mut coeffs = vec![F::zero(); xs.len()];
+    for ((x, y), w) in xs.iter().zip(ys).zip(barycentric_weights(xs)) {
+        // Synthetic division of M(X) by (X - x), from the leading coefficient down
+        let mut carry = F::zero();
+        for k in (0..xs.len()).rev() {
+            carry = master[k + 1] + *x * carry;
+            coeffs[k] += *y * w * carry;
+        }
+    }
+    DensePolynomial::from_coefficients_vec(coeffs)
+}
+
+// w_i = 1 / prod_{j != i} (xs[i] - xs[j]), which only depend on the points, so that interpolants
+// through them can then be evaluated in O(n) each
+pub fn barycentric_weights<F: Field>(xs: &[F]) -> Vec<F> {
+    let mut weights: Vec<F> = xs
+        .iter()
+        .enumerate()
+        .map(|(i, x_i)| {
+            xs.iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, x_j)| *x_i - x_j)
+                .product()
+        })
+        .collect();
+    batch_inversion(&mut weights);
+    weights
+}
+
+// The interpolant through (xs[i], ys[i]) at r, as M(r) * sum_i w_i ys[i] / (r - xs[i]) for the
+// weights from `barycentric_weights`
+pub fn barycentric_eval<F: Field>(xs: &[F], weights: &[F], ys: &[F], r: F) -> F {
+    assert_eq!(xs.len(), ys.len());
+    assert_eq!(xs.len(), weights.len());
+    let mut diffs: Vec<F> = xs.iter().map(|x| r - x).collect();
+    if let Some(i) = diffs.iter().position(|diff| diff.is_zero()) {
+        return ys[i];
+    }
+    let master: F = diffs.iter().product();
+    batch_inversion(&mut diffs);
+    master
+        * weights
+            .iter()
+            .zip(ys)
+            .zip(diffs)
+            .map(|((w, y), inv)| *w * y * inv)
+            .sum::<F>()
+}
+
+// Barycentric weights for the points 0, 1, ..., d at which sumcheck messages are given. They
+// have the closed form w_i = (-1)^(d - i) / (i! (d - i)!), so setup takes O(d) rather than O(d^2),
+// and the interpolant of evaluations at those points can then be evaluated anywhere in O(d).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interpolator<F: Field> {
+    points: Vec<F>,
+    weights: Vec<F>,
+}
+
+impl<F: Field> Interpolator<F> {
+    // The points 0, ..., degree have to be distinct in F
+    pub fn new(degree: usize) -> Self {
+        assert!(distinct_points::<F>(degree + 1));
+        let points: Vec<F> = (0..=degree).map(|i| F::from(i as u64)).collect();
+        let mut factorials = vec![F::one()];
+        for point in &points[1..] {
+            factorials.push(*factorials.last().unwrap() * point);
+        }
+        let mut weights: Vec<F> = (0..=degree)
+            .map(|i| {
+                let denominator = factorials[i] * factorials[degree - i];
+                if (degree - i) % 2 == 1 {
+                    -denominator
+                } else {
+                    denominator
+                }
+            })
+            .collect();
+        batch_inversion(&mut weights);
+        Self { points, weights }
+    }
+
+    pub fn degree(&self) -> usize {
+        self.points.len() - 1
+    }
+
+    // `evals` are the values at 0, ..., degree
+    pub fn evaluate(&self, evals: &[F], r: F) -> F {
+        barycentric_eval(&self.points, &self.weights, evals, r)
+    }
+}
+
+// Coefficients of the polynomial taking the value evals[i] at w^i, for w a primitive root of unity
+// of order evals.len(). Takes O(n log n) with an inverse FFT, and gives None unless evals.len() is a
+// power of two that the field has such a root for.
+pub fn fft_interpolate<F: FftField>(evals: &[F]) -> Option<DensePolynomial<F>> {
+    let domain = radix2_domain(evals.len())?;
+    Some(Evaluations::from_vec_and_domain(evals.to_vec(), domain).interpolate())
+}
+
+// The n-th roots of unity, for n a power of two. `ark_ff` derives the root from the field's
+// `#[generator]`, and it only has order n if that is actually a generator of F^*, so a domain
+// whose elements repeat is refused rather than giving wrong answers.
+pub fn radix2_domain<F: FftField>(n: usize) -> Option<Radix2EvaluationDomain<F>> {
+    if !n.is_power_of_two() {
+        return None;
+    }
+    let domain = Radix2EvaluationDomain::<F>::new(n)?;
+    if domain.size() != n || domain.elements().skip(1).any(|x| x == F::one()) {
+        return None;
+    }
+    Some(domain)
+}
+
+type Oracle<F> = Rc<dyn Fn(&[F]) -> F>;
+
+// Multivariate polynomial only available through an evaluation oracle, with degree bounds given
+// by the caller. Fixed variables are kept as a prefix of every query.
+#[derive(Clone)]
+pub struct OraclePolynomial<F: Field> {
+    oracle: Oracle<F>,
+    degrees: Vec<usize>,
+    fixed: Vec<F>,
+}
+
+impl<F: Field> OraclePolynomial<F> {
+    // `degrees[i]` bounds the degree of `oracle` in variable i
+    pub fn new(oracle: impl Fn(&[F]) -> F + 'static, degrees: Vec<usize>) -> Self {
+        Self {
+            oracle: Rc::new(oracle),
+            degrees,
+            fixed: vec![],
+        }
+    }
+}
+
+impl<F: Field> SumCheckPolynomial<F> for OraclePolynomial<F> {
+    fn num_vars(&self) -> usize {
+        self.degrees.len()
+    }
+
+    fn degrees(&self) -> Vec<usize> {
+        self.degrees.clone()
+    }
+
+    fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(point.len(), self.num_vars());
+        (self.oracle)(&[self.fixed.as_slice(), point].concat())
+    }
+
+    fn fix_first_variable(&self, r: F) -> Self {
+        Self {
+            oracle: self.oracle.clone(),
+            degrees: self.degrees[1..].to_vec(),
+            fixed: [self.fixed.as_slice(), &[r]].concat(),
+        }
+    }
+}
+
+// `ark_poly` numbers variables from 0, so x_0 is the first variable to be fixed.
+impl<F: Field> SumCheckPolynomial<F> for SparsePolynomial<F, SparseTerm> {
+    fn num_vars(&self) -> usize {
+        DenseMVPolynomial::num_vars(self)
+    }
+
+    fn degrees(&self) -> Vec<usize> {
+        let mut degrees = vec![0; DenseMVPolynomial::num_vars(self)];
+        for (_, term) in self.terms() {
+            for &(var, power) in term.iter() {
+                degrees[var] = degrees[var].max(power);
+            }
+        }
+        degrees
+    }
+
+    fn evaluate(&self, point: &[F]) -> F {
+        Polynomial::evaluate(self, &point.to_vec())
+    }
 
-    poly.evaluate(&r)
+    fn fix_first_variable(&self, r: F) -> Self {
+        let terms = self
+            .terms()
+            .iter()
+            .map(|(coeff, term)| {
+                let mut coeff = *coeff;
+                let mut rest = vec![];
+                for &(var, power) in term.iter() {
+                    if var == 0 {
+                        coeff *= r.pow([power as u64]);
+                    } else {
+                        rest.push((var - 1, power));
+                    }
+                }
+                (coeff, SparseTerm::new(rest))
+            })
+            .collect();
+        Self::from_coefficients_vec(DenseMVPolynomial::num_vars(self) - 1, terms)
+    }
 }
 
-/// Interpolate a degree-1 polynomial given two x-values and two corresponding y-values (evaluations).
-/// TODO dumb ChatGpt placeholder until I figure out how to do interpolation with the ark-poly
-/// crate
-fn interpolate_degree_1<F: FftField>(x_values: &[F], y_values: &[F]) -> DensePolynomial<F> {
-    // Compute the Lagrange interpolation for a degree-1 polynomial
-    let x0 = x_values[0];
-    let x1 = x_values[1];
-    let y0 = y_values[0];
-    let y1 = y_values[1];
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fields::{random_vec, Fr},
+        freivalds::F,
+    };
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_eval_poly() {
+        // 3 + 2x + x^3
+        let poly = DensePolynomial::from_coefficients_slice(&[
+            Fr::from(3),
+            Fr::from(2),
+            Fr::from(0),
+            Fr::from(1),
+        ]);
+        let evals: Vec<Fr> = (0..4).map(|x| poly.evaluate(&Fr::from(x))).collect();
+        for r in 0..5 {
+            assert_eq!(eval_poly(&evals, Fr::from(r)), poly.evaluate(&Fr::from(r)));
+        }
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        // Distinct points out of order
+        let xs = [Fr::from(3), Fr::from(0), Fr::from(4), Fr::from(1)];
+        let ys = random_vec::<Fr, _>(4, &mut rng);
+
+        let poly = lagrange_interpolate(&xs, &ys);
+        assert!(poly.degree() < 4);
+        let weights = barycentric_weights(&xs);
+        for (x, y) in xs.iter().zip(&ys) {
+            assert_eq!(poly.evaluate(x), *y);
+            assert_eq!(barycentric_eval(&xs, &weights, &ys, *x), *y);
+        }
+        let r = Fr::from(2);
+        assert_eq!(lagrange_eval(&xs, &ys, r), poly.evaluate(&r));
+        assert_eq!(barycentric_eval(&xs, &weights, &ys, r), poly.evaluate(&r));
+    }
+
+    #[test]
+    fn test_interpolator() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let points: Vec<Fr> = (0..3).map(|x| Fr::from(x as u64)).collect();
+        let interpolator = Interpolator::new(2);
+        assert_eq!(interpolator.weights, barycentric_weights(&points));
+
+        let evals = random_vec::<Fr, _>(3, &mut rng);
+        for r in 0..5 {
+            let r = Fr::from(r);
+            assert_eq!(
+                interpolator.evaluate(&evals, r),
+                lagrange_eval(&points, &evals, r)
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_interpolator_repeated_points() {
+        // 0 and 5 are the same point mod 5
+        Interpolator::<Fr>::new(5);
+    }
+
+    #[test]
+    fn test_fft_interpolate() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let evals = random_vec::<Fr, _>(4, &mut rng);
+        // The 4th roots of unity mod 5 are 1, 2, 4, 3 as powers of 2
+        let poly = fft_interpolate(&evals).unwrap();
+        let xs = [Fr::from(1), Fr::from(2), Fr::from(4), Fr::from(3)];
+        assert_eq!(poly, lagrange_interpolate(&xs, &evals));
+
+        // F_5^* has no element of order 8, and 3 is not a power of two
+        assert!(fft_interpolate(&random_vec::<Fr, _>(8, &mut rng)).is_none());
+        assert!(fft_interpolate(&random_vec::<Fr, _>(3, &mut rng)).is_none());
+
+        // Over F_17 the 16th roots of unity are all of F^*
+        let evals = random_vec::<F, _>(16, &mut rng);
+        let poly = fft_interpolate(&evals).unwrap();
+        let domain = radix2_domain::<F>(16).unwrap();
+        for (x, eval) in domain.elements().zip(&evals) {
+            assert_eq!(poly.evaluate(&x), *eval);
+        }
+    }
+
+    // 2x_1^3 + x_1x_3 + x_2x_3, the example from the book
+    fn book_example() -> SparsePolynomial<Fr, SparseTerm> {
+        SparsePolynomial::from_coefficients_vec(
+            3,
+            vec![
+                (Fr::from(2), SparseTerm::new(vec![(0, 3)])),
+                (Fr::from(1), SparseTerm::new(vec![(0, 1), (2, 1)])),
+                (Fr::from(1), SparseTerm::new(vec![(1, 1), (2, 1)])),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_sparse_fix_first_variable() {
+        let poly = book_example();
+        assert_eq!(poly.degrees(), vec![3, 1, 1]);
 
-    let term_0_coeff = y0 / (x0 - x1);
-    let term_0 = DensePolynomial::from_coefficients_slice(&[-term_0_coeff * x1, term_0_coeff]);
+        let fixed = poly.fix_first_variable(Fr::from(2));
+        assert_eq!(fixed.degrees(), vec![1, 1]);
+        for (x2, x3) in [(0, 1), (3, 4), (2, 2)] {
+            let (x2, x3) = (Fr::from(x2), Fr::from(x3));
+            assert_eq!(
+                SumCheckPolynomial::evaluate(&fixed, &[x2, x3]),
+                SumCheckPolynomial::evaluate(&poly, &[Fr::from(2), x2, x3])
+            );
+        }
+    }
 
-    let term_1_coeff = y1 / (x1 - x0);
-    let term_1 = DensePolynomial::from_coefficients_slice(&[-term_1_coeff * x0, term_1_coeff]);
+    #[test]
+    fn test_oracle_matches_sparse() {
+        let oracle = OraclePolynomial::new(crate::mle::g_poly::<Fr>, vec![3, 1, 1]);
+        let sparse = book_example();
+        assert_eq!(oracle.hypercube_sum(), sparse.hypercube_sum());
+        assert_eq!(
+            oracle.first_variable_evals(4),
+            sparse.first_variable_evals(4)
+        );
 
-    // The final polynomial is the sum of the two terms
-    &term_0 + &term_1
+        let r = Fr::from(4);
+        assert_eq!(
+            oracle.fix_first_variable(r).first_variable_evals(2),
+            sparse.fix_first_variable(r).first_variable_evals(2)
+        );
+    }
 }
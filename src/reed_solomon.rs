@@ -0,0 +1,280 @@
+use crate::polynomials::radix2_domain;
+use ark_ff::{FftField, Field, Zero};
+use ark_poly::{
+    univariate::{DenseOrSparsePolynomial, DensePolynomial},
+    DenseUVPolynomial, EvaluationDomain, Polynomial,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodeError {
+    /// The received word has `found` symbols rather than one per point of the domain
+    WrongLength { expected: usize, found: usize },
+    /// More than `max_errors` symbols differ from every codeword
+    TooManyErrors { max_errors: usize },
+}
+
+// A decoded message, along with the positions of the received word that had to be corrected
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decoded<F: Field> {
+    pub message: Vec<F>,
+    pub corrupted: Vec<usize>,
+}
+
+// Reed-Solomon code of length n = domain.len() and dimension k = message_len: a message is read as
+// the coefficients of a polynomial of degree < k, and encoded as its evaluations over the domain.
+// Two codewords agree in at most k - 1 places, so the distance is n - k + 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReedSolomon<F: Field> {
+    domain: Vec<F>,
+    message_len: usize,
+}
+
+impl<F: Field> ReedSolomon<F> {
+    // The points of the domain have to be distinct
+    pub fn new(domain: Vec<F>, message_len: usize) -> Self {
+        assert!(0 < message_len && message_len <= domain.len());
+        for (i, x) in domain.iter().enumerate() {
+            assert!(!domain[..i].contains(x));
+        }
+        Self {
+            domain,
+            message_len,
+        }
+    }
+
+    pub fn domain(&self) -> &[F] {
+        &self.domain
+    }
+
+    pub fn message_len(&self) -> usize {
+        self.message_len
+    }
+
+    pub fn distance(&self) -> usize {
+        self.domain.len() - self.message_len + 1
+    }
+
+    // Up to half the distance, which is as many as can be corrected uniquely
+    pub fn max_errors(&self) -> usize {
+        (self.distance() - 1) / 2
+    }
+
+    pub fn encode(&self, message: &[F]) -> Vec<F> {
+        assert_eq!(message.len(), self.message_len);
+        let poly = DensePolynomial::from_coefficients_slice(message);
+        self.domain.iter().map(|x| poly.evaluate(x)).collect()
+    }
+
+    // Berlekamp-Welch: with e = max_errors, finds a monic E of degree e and Q of degree < k + e
+    // with Q(x_i) = y_i E(x_i) at every point, by solving the n linear equations this gives in
+    // their k + 2e unknowns. If the received word is within e errors of the encoding of some P,
+    // every solution has Q = P * E, so P = Q / E and E vanishes where the errors are.
+    pub fn decode(&self, received: &[F]) -> Result<Decoded<F>, DecodeError> {
+        let (n, k, e) = (self.domain.len(), self.message_len, self.max_errors());
+        if received.len() != n {
+            return Err(DecodeError::WrongLength {
+                expected: n,
+                found: received.len(),
+            });
+        }
+        let too_many_errors = DecodeError::TooManyErrors { max_errors: e };
+
+        // Unknowns are the coefficients q_0, ..., q_{k+e-1} of Q, then e_0, ..., e_{e-1} of E
+        // below its leading 1:
+        //   sum_j q_j x_i^j - y_i sum_{j<e} e_j x_i^j = y_i x_i^e
+        let (rows, rhs): (Vec<Vec<F>>, Vec<F>) = self
+            .domain
+            .iter()
+            .zip(received)
+            .map(|(x, y)| {
+                let powers: Vec<F> = (0..k + e).map(|j| x.pow([j as u64])).collect();
+                let row = powers
+                    .iter()
+                    .copied()
+                    .chain(powers[..e].iter().map(|power| -*y * power))
+                    .collect();
+                (row, *y * x.pow([e as u64]))
+            })
+            .collect();
+        let solution = solve(rows, rhs).ok_or(too_many_errors.clone())?;
+
+        let q = DensePolynomial::from_coefficients_slice(&solution[..k + e]);
+        let error_locator =
+            DensePolynomial::from_coefficients_vec([&solution[k + e..], &[F::one()]].concat());
+        let (p, remainder) = DenseOrSparsePolynomial::from(q)
+            .divide_with_q_and_r(&error_locator.into())
+            .ok_or(too_many_errors.clone())?;
+        if !remainder.is_zero() || p.coeffs.len() > k {
+            return Err(too_many_errors);
+        }
+
+        let mut message = p.coeffs;
+        message.resize(k, F::zero());
+        let corrupted: Vec<usize> = self
+            .encode(&message)
+            .iter()
+            .zip(received)
+            .enumerate()
+            .filter(|(_, (codeword, y))| codeword != y)
+            .map(|(i, _)| i)
+            .collect();
+        if corrupted.len() > e {
+            return Err(too_many_errors);
+        }
+        Ok(Decoded { message, corrupted })
+    }
+}
+
+impl<F: FftField> ReedSolomon<F> {
+    // Over the n-th roots of unity, for n a power of two that the field has such roots for
+    pub fn radix2(n: usize, message_len: usize) -> Option<Self> {
+        let domain = radix2_domain::<F>(n)?;
+        Some(Self::new(domain.elements().collect(), message_len))
+    }
+}
+
+// Some solution of the linear system rows * x = rhs, with the free variables set to zero, or None
+// if there is none. Gaussian elimination in O(n^3).
+fn solve<F: Field>(mut rows: Vec<Vec<F>>, mut rhs: Vec<F>) -> Option<Vec<F>> {
+    let num_unknowns = rows.first().map_or(0, |row| row.len());
+    let mut pivots = vec![];
+    for col in 0..num_unknowns {
+        let rank = pivots.len();
+        let Some(pivot) = (rank..rows.len()).find(|i| !rows[*i][col].is_zero()) else {
+            continue;
+        };
+        rows.swap(rank, pivot);
+        rhs.swap(rank, pivot);
+
+        let inverse = rows[rank][col].inverse().unwrap();
+        rows[rank].iter_mut().for_each(|entry| *entry *= inverse);
+        rhs[rank] *= inverse;
+        for i in (0..rows.len()).filter(|i| *i != rank) {
+            let factor = rows[i][col];
+            if factor.is_zero() {
+                continue;
+            }
+            let pivot_row = rows[rank].clone();
+            for (entry, pivot_entry) in rows[i].iter_mut().zip(pivot_row).skip(col) {
+                *entry -= factor * pivot_entry;
+            }
+            let value = rhs[rank];
+            rhs[i] -= factor * value;
+        }
+        pivots.push(col);
+    }
+
+    // Rows left without a pivot read 0 = rhs
+    if rhs[pivots.len()..].iter().any(|value| !value.is_zero()) {
+        return None;
+    }
+    let mut solution = vec![F::zero(); num_unknowns];
+    for (row, col) in pivots.into_iter().enumerate() {
+        solution[col] = rhs[row];
+    }
+    Some(solution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fields::{random_vec, Fr},
+        freivalds::F,
+    };
+    use ark_ff::{One, PrimeField};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_encode() {
+        // 1 + 2X over 0, 1, 2, 3
+        let code = ReedSolomon::new((0..4).map(F::from).collect(), 2);
+        assert_eq!(code.distance(), 3);
+        assert_eq!(code.max_errors(), 1);
+        assert_eq!(
+            code.encode(&[F::from(1), F::from(2)]),
+            vec![F::from(1), F::from(3), F::from(5), F::from(7)]
+        );
+    }
+
+    #[test]
+    fn test_decode_corrects_errors() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let code = ReedSolomon::new((0..16).map(F::from).collect(), 6);
+        assert_eq!(code.max_errors(), 5);
+        let message = random_vec::<F, _>(6, &mut rng);
+        let codeword = code.encode(&message);
+
+        let decoded = code.decode(&codeword).unwrap();
+        assert_eq!(decoded.message, message);
+        assert!(decoded.corrupted.is_empty());
+
+        let mut received = codeword.clone();
+        let corrupted = vec![0, 3, 4, 9, 15];
+        for i in &corrupted {
+            received[*i] += F::one();
+        }
+        let decoded = code.decode(&received).unwrap();
+        assert_eq!(decoded.message, message);
+        assert_eq!(decoded.corrupted, corrupted);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let code = ReedSolomon::new((0..8).map(F::from).collect(), 2);
+        let codeword = code.encode(&[F::from(3), F::from(5)]);
+        assert_eq!(
+            code.decode(&codeword[1..]),
+            Err(DecodeError::WrongLength {
+                expected: 8,
+                found: 7
+            })
+        );
+
+        // Past half the distance: 4 errors with max_errors = 3. Adding 1 to the first four
+        // symbols and 0 elsewhere is not close to any codeword of degree < 2.
+        let mut received = codeword.clone();
+        for symbol in &mut received[..4] {
+            *symbol += F::one();
+        }
+        assert_eq!(
+            code.decode(&received),
+            Err(DecodeError::TooManyErrors { max_errors: 3 })
+        );
+    }
+
+    #[test]
+    fn test_radix2() {
+        // The 4th roots of unity mod 5 are 1, 2, 4, 3
+        let code = ReedSolomon::<Fr>::radix2(4, 2).unwrap();
+        assert_eq!(
+            code.domain(),
+            &[Fr::from(1), Fr::from(2), Fr::from(4), Fr::from(3)]
+        );
+        let mut received = code.encode(&[Fr::from(4), Fr::from(1)]);
+        received[2] += Fr::from(2);
+        let decoded = code.decode(&received).unwrap();
+        assert_eq!(decoded.message, vec![Fr::from(4), Fr::from(1)]);
+        assert_eq!(decoded.corrupted, vec![2]);
+
+        // F_5^* has no element of order 8
+        assert!(ReedSolomon::<Fr>::radix2(8, 2).is_none());
+
+        // Over F_17 the 16th roots of unity are all of F^*
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let code = ReedSolomon::<F>::radix2(16, 6).unwrap();
+        let mut domain = code.domain().to_vec();
+        domain.sort_by_key(|x| x.into_bigint());
+        assert_eq!(domain, (1..17).map(F::from).collect::<Vec<_>>());
+
+        let message = random_vec::<F, _>(6, &mut rng);
+        let mut received = code.encode(&message);
+        for i in [1, 2, 7, 11, 14] {
+            received[i] -= F::one();
+        }
+        let decoded = code.decode(&received).unwrap();
+        assert_eq!(decoded.message, message);
+        assert_eq!(decoded.corrupted, vec![1, 2, 7, 11, 14]);
+    }
+}
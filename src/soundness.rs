@@ -0,0 +1,154 @@
+use crate::{adversary::Adversary, ip::*};
+use ark_std::fmt;
+use rand::Rng;
+
+// z-score for a 95% confidence interval
+const Z_95: f64 = 1.96;
+
+// Protocols that know their own soundness error, i.e. an upper bound on the probability that the
+// verifier accepts when the prover deviates from the honest strategy.
+pub trait SoundnessBound: IP {
+    fn soundness_error(input: &Self::Input) -> f64;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoundnessReport {
+    pub runs: usize,
+    pub accepted: usize,
+    pub acceptance_rate: f64,
+    // 95% Wilson score interval for the acceptance probability
+    pub confidence_interval: (f64, f64),
+    pub bound: f64,
+}
+
+impl SoundnessReport {
+    pub fn new(runs: usize, accepted: usize, bound: f64) -> Self {
+        assert!(runs > 0);
+        let n = runs as f64;
+        let p = accepted as f64 / n;
+        let z2 = Z_95 * Z_95;
+        let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+        let half_width = Z_95 * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / (1.0 + z2 / n);
+        Self {
+            runs,
+            accepted,
+            acceptance_rate: p,
+            confidence_interval: (
+                (center - half_width).max(0.0),
+                (center + half_width).min(1.0),
+            ),
+            bound,
+        }
+    }
+
+    // Whether the experiment is consistent with the theoretical bound, i.e. the bound is not
+    // below the confidence interval.
+    pub fn within_bound(&self) -> bool {
+        self.confidence_interval.0 <= self.bound
+    }
+}
+
+impl fmt::Display for SoundnessReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Runs: {} \n Accepted: {} \n Acceptance rate: {:.4} (95% CI [{:.4}, {:.4}]) \n Soundness bound: {:.4}",
+            self.runs,
+            self.accepted,
+            self.acceptance_rate,
+            self.confidence_interval.0,
+            self.confidence_interval.1,
+            self.bound
+        )
+    }
+}
+
+// Runs the protocol on `input` against `adversary` `runs` times with fresh verifier coins, and
+// reports how often the verifier accepted. Each run is seeded from `rng`, so the whole experiment
+// is reproducible from the seed of `rng`.
+pub fn estimate_soundness<I, A, R>(
+    input: &I::Input,
+    adversary: &A,
+    runs: usize,
+    rng: &mut R,
+) -> SoundnessReport
+where
+    I: SoundnessBound,
+    I::Input: Clone,
+    A: Adversary<I> + Clone,
+    R: Rng,
+{
+    let accepted = (0..runs)
+        .filter(|_| {
+            let mut ip = Driver::<I, A>::with_seed(input.clone(), adversary.clone(), rng.gen());
+            // Rejections are the expected outcome here
+            ip.log_rejections = false;
+            ip.run() == Status::Accepted
+        })
+        .count();
+    SoundnessReport::new(runs, accepted, I::soundness_error(input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        adversary::{CorruptEntry, Honest, LieInRound},
+        fields::Fr,
+        freivalds::{MatMulIP, Matrix, F},
+        mle::random_evals,
+        sumcheck::SumCheck,
+    };
+    use ark_ff::One;
+    use ndarray::Array2;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_wilson_interval() {
+        let report = SoundnessReport::new(100, 50, 0.5);
+        assert!((report.confidence_interval.0 - 0.4038).abs() < 1e-3);
+        assert!((report.confidence_interval.1 - 0.5962).abs() < 1e-3);
+
+        let report = SoundnessReport::new(100, 0, 0.0);
+        assert_eq!(report.confidence_interval.0, 0.0);
+        assert!(report.within_bound());
+    }
+
+    #[test]
+    fn test_sumcheck_soundness() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let v = 3;
+        let evals = random_evals::<Fr, _>(v, &mut rng);
+
+        let honest = estimate_soundness::<SumCheck<Fr>, _, _>(&evals, &Honest, 100, &mut rng);
+        assert_eq!(honest.accepted, 100);
+
+        let report = estimate_soundness::<SumCheck<Fr>, _, _>(
+            &evals,
+            &LieInRound { round: 0 },
+            1000,
+            &mut rng,
+        );
+        assert_eq!(report.bound, 3.0 / 5.0);
+        assert!(report.accepted > 0);
+        assert!(report.within_bound());
+    }
+
+    #[test]
+    fn test_freivalds_soundness() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let n = 4;
+        let a = Matrix(Array2::<F>::from_elem((n, n), F::one()));
+        let b = Matrix(Array2::<F>::from_elem((n, n), F::one() + F::one()));
+
+        let report = estimate_soundness::<MatMulIP, _, _>(
+            &(a, b),
+            &CorruptEntry::every_round(0),
+            1000,
+            &mut rng,
+        );
+        assert_eq!(report.bound, 3.0 / 17.0);
+        assert!(report.within_bound());
+    }
+}
@@ -1,145 +1,902 @@
 use crate::{
-    fields::{evals_sum, random_elem},
+    fields::{distinct_points, evals_sum, field_size},
     ip::*,
-    mle::{calculate_g_i, random_evals, stream_eval},
-    polynomials::eval_poly,
+    mle::{dense_eval, first_variable_evals, fix_first_variable, index_to_vars},
+    oracle::{PolynomialOracle, SelfEvaluation},
+    polynomials::Interpolator,
+    soundness::SoundnessBound,
+    transcript::Transcript,
 };
 use ark_ff::{FftField, Field};
-use ark_std::iterable::Iterable;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::marker::PhantomData;
+use rand::Rng;
+
+// A polynomial in v variables that sumcheck can be run on: an evaluation oracle along with a bound
+// on its degree in each variable. The prover works with the polynomial directly, fixing one
+// variable per round.
+pub trait SumCheckPolynomial<F: Field>: Clone {
+    fn num_vars(&self) -> usize;
+
+    // Bound on the degree in each variable, so round i's message has degrees()[i] + 1 evaluations
+    fn degrees(&self) -> Vec<usize>;
+
+    fn evaluate(&self, point: &[F]) -> F;
+
+    // Sumcheck is over {0, ..., m - 1}^v for m = domain_size(), the hypercube unless overridden.
+    // Polynomials over a larger grid have to override `first_variable_evals` and `hypercube_sum`
+    // too, since the defaults only sum over {0, 1}.
+    fn domain_size(&self) -> usize {
+        2
+    }
+
+    // Substitutes r for the first variable, leaving a polynomial in the remaining v - 1
+    fn fix_first_variable(&self, r: F) -> Self;
+
+    // Evaluations at 0, ..., num_points - 1 of the univariate polynomial in the first variable
+    // with the rest summed over the hypercube. Costs num_points * 2^(v - 1) oracle queries unless
+    // overridden.
+    fn first_variable_evals(&self, num_points: usize) -> Vec<F> {
+        let rest = self.num_vars() - 1;
+        (0..num_points)
+            .map(|x| {
+                (0..1 << rest)
+                    .map(|index| {
+                        let point = [vec![F::from(x as u64)], index_to_vars(index, rest)].concat();
+                        self.evaluate(&point)
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+
+    fn hypercube_sum(&self) -> F {
+        let v = self.num_vars();
+        (0..1 << v)
+            .map(|index| self.evaluate(&index_to_vars(index, v)))
+            .sum()
+    }
+}
+
+// A multilinear polynomial given by its evaluations over the boolean hypercube, which the prover
+// folds in linear time (see `mle::fix_first_variable`).
+impl<F: Field> SumCheckPolynomial<F> for Vec<F> {
+    fn num_vars(&self) -> usize {
+        assert!(self.len().is_power_of_two());
+        self.len().trailing_zeros() as usize
+    }
+
+    fn degrees(&self) -> Vec<usize> {
+        vec![1; self.num_vars()]
+    }
+
+    fn evaluate(&self, point: &[F]) -> F {
+        dense_eval(point, self)
+    }
+
+    fn fix_first_variable(&self, r: F) -> Self {
+        fix_first_variable(self, r)
+    }
+
+    fn first_variable_evals(&self, num_points: usize) -> Vec<F> {
+        first_variable_evals(self, num_points)
+    }
+
+    fn hypercube_sum(&self) -> F {
+        evals_sum(self)
+    }
+}
+
+// IP representing the sum check protocol, over the hypercube evaluations of a multilinear
+// polynomial unless another `SumCheckPolynomial` is given. The verifier's final check is a single
+// query to an `O` built from the polynomial, by default evaluating it itself.
+pub struct SumCheck<
+    F: FftField,
+    P: SumCheckPolynomial<F> = Vec<F>,
+    O: PolynomialOracle<F> + From<P> = SelfEvaluation<F, P>,
+>(PhantomData<(F, P, O)>);
+
+// Keeps the polynomial with the challenges received so far fixed, which loses a variable every
+// round.
+#[derive(Debug, Clone)]
+pub struct SumCheckProver<F: FftField, P: SumCheckPolynomial<F>> {
+    poly: P,
+    // Degree bounds of the input polynomial. Fixing variables can lower the degree of the ones
+    // left, but messages are sized by the bounds the verifier knows.
+    degrees: Vec<usize>,
+    round: usize,
+    _field: PhantomData<F>,
+}
 
-// IP representing the sum check protocol
 #[derive(Debug, Clone)]
-struct SumCheck<F: FftField> {
+pub struct SumCheckVerifier<
+    F: FftField,
+    P: SumCheckPolynomial<F>,
+    O: PolynomialOracle<F> + From<P> = SelfEvaluation<F, P>,
+> {
     status: Status,
-    prover_mode: ProverMode,
+    // Bound on the degree of each g_i
+    degrees: Vec<usize>,
+    // Number of points each variable is summed over
+    domain_size: usize,
+    // One per degree up to the highest bound, shared by the rounds
+    interpolators: Vec<Interpolator<F>>,
     r: Vec<F>,
-    univariate_evals: Vec<Vec<F>>,
-    hypercube_evals: Vec<F>,
-    g_sum: F,
+    // The latest g_i
+    message: Vec<F>,
+    // Only used for the final evaluation of the polynomial at the random point
+    oracle: O,
+    // What g_i(0) + ... + g_i(m - 1) has to equal: the claimed sum in the first round, and
+    // g_{i-1}(r_{i-1}) after that
+    claim: F,
     v: usize,
+    _poly: PhantomData<P>,
+}
+
+impl<F: FftField, P: SumCheckPolynomial<F>, O: PolynomialOracle<F> + From<P>>
+    SumCheckVerifier<F, P, O>
+{
+    pub fn oracle(&self) -> &O {
+        &self.oracle
+    }
 }
 
-impl<F> IP for SumCheck<F>
+impl<F, P, O> IP for SumCheck<F, P, O>
 where
     F: FftField,
+    P: SumCheckPolynomial<F>,
+    O: PolynomialOracle<F> + From<P>,
 {
-    // Verifier sends random values, verifier sends values representing full evaluations of the
-    // univariate polynomial g_i over field F.
-    //
-    // TODO: modify to send (0,...,deg_(g)) evaluations once we start using bigger fields.
+    // Prover sends the evaluations of the univariate polynomial g_i at 0, ..., deg(g_i), verifier
+    // sends random values.
     type ProverMessage = Vec<F>;
     type VerifierMessage = F;
 
-    // Input is just v, the number of variables for some random set of evaluations of the boolean
-    // hypercube over a multilinear polynomial.
-    type Input = usize;
+    type Input = P;
+    type ProverInput = P;
+    // The claimed sum, and the oracle the verifier queries for its final check.
+    type VerifierInput = (F, O);
+    type Prover = SumCheckProver<F, P>;
+    type Verifier = SumCheckVerifier<F, P, O>;
 
-    fn initialize(input: Self::Input, prover_mode: ProverMode) -> Self {
-        let evals = random_evals(input);
-        let sum = evals_sum(&evals);
+    fn split_input(input: Self::Input) -> (Self::ProverInput, Self::VerifierInput) {
+        let sum = input.hypercube_sum();
+        (input.clone(), (sum, O::from(input)))
+    }
+}
+
+// sum_i deg_i / |F|, which is v / |F| for a multilinear polynomial
+impl<F, P, O> SoundnessBound for SumCheck<F, P, O>
+where
+    F: FftField,
+    P: SumCheckPolynomial<F>,
+    O: PolynomialOracle<F> + From<P>,
+{
+    fn soundness_error(input: &Self::Input) -> f64 {
+        input.degrees().iter().sum::<usize>() as f64 / field_size::<F>()
+    }
+}
+
+// The prover does not depend on how the verifier queries the polynomial, so protocols that run
+// sumcheck inside them can drive it directly.
+impl<F: FftField, P: SumCheckPolynomial<F>> SumCheckProver<F, P> {
+    pub fn new(poly: P) -> Self {
         Self {
-            status: Status::Running,
-            prover_mode,
+            degrees: poly.degrees(),
+            poly,
+            round: 0,
+            _field: PhantomData,
+        }
+    }
+
+    pub fn receive_challenge(&mut self, r: F) {
+        // The challenge in the final round is not followed by another message
+        if self.poly.num_vars() > 1 {
+            self.poly = self.poly.fix_first_variable(r);
+        }
+        self.round += 1;
+    }
+
+    pub fn round_message(&self) -> Vec<F> {
+        self.poly.first_variable_evals(self.degrees[self.round] + 1)
+    }
+}
+
+impl<F, P, O> Prover<SumCheck<F, P, O>> for SumCheckProver<F, P>
+where
+    F: FftField,
+    P: SumCheckPolynomial<F>,
+    O: PolynomialOracle<F> + From<P>,
+{
+    fn initialize(input: P) -> Self {
+        Self::new(input)
+    }
+
+    fn receive_verifier_message(&mut self, message: F) {
+        self.receive_challenge(message)
+    }
+
+    fn run_prover_logic(&mut self) -> Vec<F> {
+        self.round_message()
+    }
+}
+
+impl<F, P, O> Verifier<SumCheck<F, P, O>> for SumCheckVerifier<F, P, O>
+where
+    F: FftField,
+    P: SumCheckPolynomial<F>,
+    O: PolynomialOracle<F> + From<P>,
+{
+    fn initialize(input: (F, O)) -> Self {
+        let (claim, mut oracle) = input;
+        let interpolators = interpolators(&oracle.degrees());
+        // With no variables there are no rounds: the sum is the polynomial's only value, which
+        // the verifier can check with a single query before the prover says anything. Degree
+        // bounds the field cannot interpolate are rejected before any round, too.
+        let status = match oracle.num_vars() {
+            _ if interpolators.is_err() => Status::Rejected,
+            0 if oracle.query(&[]) == Some(claim) => Status::Accepted,
+            0 => Status::Rejected,
+            _ => Status::Running,
+        };
+        Self {
+            status,
+            interpolators: interpolators.unwrap_or_default(),
+            degrees: oracle.degrees(),
+            domain_size: oracle.domain_size(),
             r: vec![],
-            univariate_evals: vec![],
-            // TODO: make this less contrived and actually pass in the polynomial.
-            hypercube_evals: evals,
-            g_sum: sum,
-            v: input,
+            message: vec![],
+            v: oracle.num_vars(),
+            oracle,
+            claim,
+            _poly: PhantomData,
         }
     }
 
-    fn run_honest_prover_logic(&mut self) -> Self::ProverMessage {
-        // Assert that v = log_2(len(evals))
-        let g_i_evals = calculate_g_i(&self.r, &self.hypercube_evals, self.v);
-        g_i_evals
+    fn receive_prover_message(&mut self, message: Vec<F>) {
+        self.message = message;
     }
 
-    fn run_malicious_prover_logic(&mut self) -> Self::ProverMessage {
-        // Assert that v = log_2(len(evals))
-        let mut g_i_evals = calculate_g_i(&self.r, &self.hypercube_evals, self.v);
-        g_i_evals[0] += F::one(); // tamper with the univariate polynomial at xi =0, which will
-                                  // affect the sum check
-        g_i_evals
+    fn run_verifier_logic<R: Rng>(&mut self, rng: &mut R) -> F {
+        let round = self.r.len();
+        let r = F::rand(rng);
+        self.r.push(r);
+
+        let degree = self.degrees[round];
+        match verify_round(
+            self.claim,
+            &self.message,
+            degree,
+            self.domain_size,
+            &self.interpolators,
+            round,
+            r,
+        ) {
+            Err(_) => self.status = Status::Rejected,
+            Ok(claim) => {
+                self.claim = claim;
+                if round == self.v - 1 {
+                    self.status = match self.oracle.query(&self.r) {
+                        Some(value) if value == claim => Status::Accepted,
+                        _ => Status::Rejected,
+                    };
+                }
+            }
+        }
+        r
     }
 
     fn get_status(&self) -> Status {
         self.status.clone()
     }
+}
 
-    fn run_verifier_logic(&mut self) -> Self::VerifierMessage {
-        let univariate_evals = self.get_last_prover_message();
-        let round_num = self.r.len();
-        let univariate_sum = univariate_evals[0] + univariate_evals[1];
-        let rand_response = random_elem::<F>();
+// All of the prover's messages in a run of sumcheck, g_1, ..., g_v as evaluations at 0, 1, ...
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SumcheckProof<F: Field> {
+    pub round_messages: Vec<Vec<F>>,
+}
 
-        if round_num == 0 {
-            // In the first round, verify that the univariate polynomial sums to the hypercube eval sum
-            let sum_check_success = univariate_sum == self.g_sum;
-            if !sum_check_success {
-                dbg!("failing in first round");
-                self.status = Status::Rejected;
-            }
-        } else if round_num < self.v - 1 {
-            let previous_poly = &self.univariate_evals[round_num - 1];
-            let previous_r = self.r[round_num - 1];
-            // For intermediate rounds, verify that the current univariate polynomial sums to the random
-            // evaluation of the last univariate polynomial
-            let sum_check_success = univariate_sum == eval_poly(previous_poly, previous_r);
-            if !sum_check_success {
-                self.status = Status::Rejected;
+impl<F: Field> SumcheckProof<F> {
+    // Splits a recorded run of `SumCheck` into the proof and the challenges it was checked with.
+    pub fn from_transcript(transcript: &Transcript<Vec<F>, F>) -> (Self, Vec<F>) {
+        let proof = Self {
+            round_messages: transcript.prover_messages().cloned().collect(),
+        };
+        (proof, transcript.verifier_messages().copied().collect())
+    }
+}
+
+// What is left to check once every round of sumcheck has passed: that the polynomial evaluates
+// to `expected_value` at `point`, usually with a single oracle query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubClaim<F> {
+    pub point: Vec<F>,
+    pub expected_value: F,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SumcheckError {
+    /// The proof or the challenges do not have one entry per variable
+    WrongNumberOfRounds { expected: usize, found: usize },
+    /// g_round has no evaluations, or more than the degree bound allows
+    DegreeBound { round: usize },
+    /// The sum of g_round over the domain differs from the claim left by the previous round
+    InconsistentSum { round: usize },
+    /// The degree bound for g_round needs more evaluation points 0, 1, ... than F has
+    FieldTooSmall { round: usize },
+}
+
+// Checks g_round against the current claim and reduces it to the claim g_round(r) for the next
+// round. g_round is given by its evaluations at 0, 1, ..., so fewer than degree + 1 of them means
+// a polynomial of lower degree. The claim is a sum over {0, ..., domain_size - 1}.
+// `interpolators` come from `interpolators`, for bounds at least as high as `degree`.
+fn verify_round<F: Field>(
+    claim: F,
+    message: &[F],
+    degree: usize,
+    domain_size: usize,
+    interpolators: &[Interpolator<F>],
+    round: usize,
+    r: F,
+) -> Result<F, SumcheckError> {
+    if message.is_empty() || message.len() > degree + 1 {
+        return Err(SumcheckError::DegreeBound { round });
+    }
+    let interpolator = &interpolators[message.len() - 1];
+    let sum: F = (0..domain_size)
+        .map(|x| interpolator.evaluate(message, F::from(x as u64)))
+        .sum();
+    if sum != claim {
+        return Err(SumcheckError::InconsistentSum { round });
+    }
+    Ok(interpolator.evaluate(message, r))
+}
+
+// Interpolators for messages of every degree up to the highest of the bounds, so that each is
+// set up once per run rather than once per round. Fails for the first round whose bound is too
+// high for a message to be given at distinct points.
+fn interpolators<F: Field>(degrees: &[usize]) -> Result<Vec<Interpolator<F>>, SumcheckError> {
+    if let Some(round) = degrees.iter().position(|d| !distinct_points::<F>(d + 1)) {
+        return Err(SumcheckError::FieldTooSmall { round });
+    }
+    let max_degree = degrees.iter().copied().max().unwrap_or(0);
+    Ok((0..=max_degree).map(Interpolator::new).collect())
+}
+
+// Runs the verifier's side of every round of sumcheck for the claim that a polynomial in
+// `degrees.len()` variables, of degree at most `degrees[i]` in variable i + 1, sums to
+// `claimed_sum` over {0, ..., domain_size - 1}^v (the hypercube for domain_size = 2). Leaves the
+// final evaluation to the caller, so that sumcheck can be embedded in larger protocols.
+pub fn verify_sumcheck<F: Field>(
+    claimed_sum: F,
+    degrees: &[usize],
+    domain_size: usize,
+    proof: &SumcheckProof<F>,
+    challenges: &[F],
+) -> Result<SubClaim<F>, SumcheckError> {
+    let num_vars = degrees.len();
+    for found in [proof.round_messages.len(), challenges.len()] {
+        if found != num_vars {
+            return Err(SumcheckError::WrongNumberOfRounds {
+                expected: num_vars,
+                found,
+            });
+        }
+    }
+
+    let interpolators = interpolators(degrees)?;
+    let mut claim = claimed_sum;
+    let rounds = proof.round_messages.iter().zip(degrees).zip(challenges);
+    for (round, ((message, degree), r)) in rounds.enumerate() {
+        claim = verify_round(
+            claim,
+            message,
+            *degree,
+            domain_size,
+            &interpolators,
+            round,
+            *r,
+        )?;
+    }
+    Ok(SubClaim {
+        point: challenges.to_vec(),
+        expected_value: claim,
+    })
+}
+
+// The linear combination sum_j coeffs[j] * polys[j] of polynomials in the same variables
+#[derive(Debug, Clone)]
+pub struct BatchedPolynomial<F: Field, P: SumCheckPolynomial<F>> {
+    polys: Vec<P>,
+    coeffs: Vec<F>,
+}
+
+impl<F: Field, P: SumCheckPolynomial<F>> BatchedPolynomial<F, P> {
+    // Combines with coefficients 1, alpha, alpha^2, ...
+    pub fn new(polys: Vec<P>, alpha: F) -> Self {
+        assert!(!polys.is_empty());
+        assert!(polys.iter().all(|p| p.num_vars() == polys[0].num_vars()));
+        let coeffs = powers(alpha, polys.len());
+        Self { polys, coeffs }
+    }
+
+    fn combine(&self, values: impl Iterator<Item = F>) -> F {
+        self.coeffs
+            .iter()
+            .zip(values)
+            .map(|(c, value)| *c * value)
+            .sum()
+    }
+}
+
+impl<F: Field, P: SumCheckPolynomial<F>> SumCheckPolynomial<F> for BatchedPolynomial<F, P> {
+    fn num_vars(&self) -> usize {
+        self.polys[0].num_vars()
+    }
+
+    fn degrees(&self) -> Vec<usize> {
+        let mut degrees = vec![0; self.num_vars()];
+        for poly in &self.polys {
+            for (degree, d) in degrees.iter_mut().zip(poly.degrees()) {
+                *degree = (*degree).max(d);
             }
-        } else {
-            let last_r = [self.r.as_slice(), &[rand_response]].concat();
-            let sum_check_pass = eval_poly(&univarate_evals, rand_response)
-                == stream_eval(&last_r, &self.hypercube_evals, self.v);
-            if !sum_check_pass {
-                self.status = Status::Rejected;
-            } else {
-                self.status = Status::Accepted;
+        }
+        degrees
+    }
+
+    fn domain_size(&self) -> usize {
+        self.polys[0].domain_size()
+    }
+
+    // Fans out to one query per polynomial
+    fn evaluate(&self, point: &[F]) -> F {
+        self.combine(self.polys.iter().map(|p| p.evaluate(point)))
+    }
+
+    fn fix_first_variable(&self, r: F) -> Self {
+        Self {
+            polys: self.polys.iter().map(|p| p.fix_first_variable(r)).collect(),
+            coeffs: self.coeffs.clone(),
+        }
+    }
+
+    fn first_variable_evals(&self, num_points: usize) -> Vec<F> {
+        let mut evals = vec![F::zero(); num_points];
+        for (c, poly) in self.coeffs.iter().zip(&self.polys) {
+            for (eval, e) in evals.iter_mut().zip(poly.first_variable_evals(num_points)) {
+                *eval += *c * e;
             }
         }
-        rand_response
+        evals
     }
 
-    fn add_prover_message(&mut self, message: Self::ProverMessage) {
-        self.univariate_evals.push(message)
+    fn hypercube_sum(&self) -> F {
+        self.combine(self.polys.iter().map(|p| p.hypercube_sum()))
     }
+}
 
-    fn add_verifier_message(&mut self, message: Self::VerifierMessage) {
-        self.r.push(message)
+fn powers<F: Field>(alpha: F, k: usize) -> Vec<F> {
+    (0..k)
+        .scan(F::one(), |power, _| {
+            let current = *power;
+            *power *= alpha;
+            Some(current)
+        })
+        .collect()
+}
+
+// Proves the sums of k polynomials over the same hypercube with a single sumcheck. The verifier
+// already holds the k claims, so the prover opens with an empty message, the verifier replies with
+// a random alpha, and the rest is sumcheck for sum_j alpha^j * claim_j on sum_j alpha^j * P_j,
+// whose final query fans out to each P_j.
+pub struct BatchedSumCheck<F: FftField, P: SumCheckPolynomial<F> = Vec<F>>(PhantomData<(F, P)>);
+
+#[derive(Debug, Clone)]
+pub struct BatchedSumCheckProver<F: FftField, P: SumCheckPolynomial<F>> {
+    polys: Vec<P>,
+    // Started once alpha is known
+    sumcheck: Option<SumCheckProver<F, BatchedPolynomial<F, P>>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchedSumCheckVerifier<F: FftField, P: SumCheckPolynomial<F>> {
+    claims: Vec<F>,
+    polys: Vec<P>,
+    sumcheck: Option<SumCheckVerifier<F, BatchedPolynomial<F, P>>>,
+}
+
+impl<F, P> IP for BatchedSumCheck<F, P>
+where
+    F: FftField,
+    P: SumCheckPolynomial<F>,
+{
+    // Empty in round 0, then sumcheck messages
+    type ProverMessage = Vec<F>;
+    // alpha in round 0, then sumcheck challenges
+    type VerifierMessage = F;
+
+    type Input = Vec<P>;
+    type ProverInput = Vec<P>;
+    // The claimed sums, and the polynomials for the final check
+    type VerifierInput = (Vec<F>, Vec<P>);
+    type Prover = BatchedSumCheckProver<F, P>;
+    type Verifier = BatchedSumCheckVerifier<F, P>;
+
+    fn split_input(input: Self::Input) -> (Self::ProverInput, Self::VerifierInput) {
+        let sums = input.iter().map(|p| p.hypercube_sum()).collect();
+        (input.clone(), (sums, input))
     }
+}
 
-    fn total_messages(&self) -> usize {
-        self.r.len() + self.univariate_evals.len()
+// If any claim is false, sum_j alpha^j * (claim_j - sum of P_j) is a nonzero polynomial of degree
+// k - 1 in alpha, so (k - 1) / |F| for the combined claim to come out right, plus sumcheck's error
+// on the combined polynomial
+impl<F: FftField, P: SumCheckPolynomial<F>> SoundnessBound for BatchedSumCheck<F, P> {
+    fn soundness_error(input: &Self::Input) -> f64 {
+        let combined = BatchedPolynomial::new(input.clone(), F::one());
+        (input.len() - 1) as f64 / field_size::<F>()
+            + SumCheck::<F, BatchedPolynomial<F, P>>::soundness_error(&combined)
     }
+}
 
-    fn get_last_prover_message(&self) -> Self::ProverMessage {
-        self.univariate_evals[self.univariate_evals.len() - 1].clone()
+impl<F: FftField, P: SumCheckPolynomial<F>> Prover<BatchedSumCheck<F, P>>
+    for BatchedSumCheckProver<F, P>
+{
+    fn initialize(input: Vec<P>) -> Self {
+        Self {
+            polys: input,
+            sumcheck: None,
+        }
+    }
+
+    fn receive_verifier_message(&mut self, message: F) {
+        match &mut self.sumcheck {
+            Some(sumcheck) => sumcheck.receive_challenge(message),
+            None => {
+                let combined = BatchedPolynomial::new(std::mem::take(&mut self.polys), message);
+                self.sumcheck = Some(SumCheckProver::new(combined));
+            }
+        }
+    }
+
+    fn run_prover_logic(&mut self) -> Vec<F> {
+        match &mut self.sumcheck {
+            Some(sumcheck) => sumcheck.round_message(),
+            None => vec![],
+        }
+    }
+}
+
+impl<F: FftField, P: SumCheckPolynomial<F>> Verifier<BatchedSumCheck<F, P>>
+    for BatchedSumCheckVerifier<F, P>
+{
+    fn initialize(input: (Vec<F>, Vec<P>)) -> Self {
+        let (claims, polys) = input;
+        assert_eq!(claims.len(), polys.len());
+        Self {
+            claims,
+            polys,
+            sumcheck: None,
+        }
     }
 
-    fn get_last_verifier_message(&self) -> Self::VerifierMessage {
-        self.r[self.r.len()]
+    fn receive_prover_message(&mut self, message: Vec<F>) {
+        // Nothing to check in the prover's empty opening message
+        if let Some(sumcheck) = &mut self.sumcheck {
+            sumcheck.receive_prover_message(message);
+        }
     }
 
-    fn get_prover_mode(&self) -> ProverMode {
-        self.prover_mode.clone()
+    fn run_verifier_logic<R: Rng>(&mut self, rng: &mut R) -> F {
+        if let Some(sumcheck) = &mut self.sumcheck {
+            return sumcheck.run_verifier_logic(rng);
+        }
+
+        let alpha = F::rand(rng);
+        let combined = BatchedPolynomial::new(std::mem::take(&mut self.polys), alpha);
+        let claim = combined.combine(self.claims.iter().copied());
+        self.sumcheck = Some(SumCheckVerifier::initialize((claim, combined.into())));
+        alpha
+    }
+
+    fn get_status(&self) -> Status {
+        match &self.sumcheck {
+            Some(sumcheck) => sumcheck.get_status(),
+            None => Status::Running,
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::fields::Fr;
+    use crate::{
+        adversary::{CorruptEntry, Honest, LieInRound, RandomMessage},
+        fields::{random_elem, Fr},
+        mle::{g_poly, random_evals, DenseMLE, ProductMLE, SparseMLE, StreamingMLE, VariableOrder},
+        polynomials::OraclePolynomial,
+    };
+    use ark_poly::polynomial::multivariate::{SparsePolynomial, SparseTerm, Term};
+    use ark_poly::DenseMVPolynomial;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
 
     #[test]
     fn test_sumcheck_ip() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
         let v = 8;
 
-        let mut honest_sumcheck_ip: SumCheck<Fr> = SumCheck::initialize(v, ProverMode::Honest);
+        let mut honest_sumcheck_ip = Driver::<SumCheck<Fr>>::new(random_evals(v, &mut rng));
         for _ in 0..v * 2 {
             let _ = honest_sumcheck_ip.step();
         }
         assert_eq!(honest_sumcheck_ip.get_status(), Status::Accepted);
     }
+
+    #[test]
+    fn test_sumcheck_large() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let v = 16;
+
+        let mut honest_sumcheck_ip = Driver::<SumCheck<Fr>>::new(random_evals(v, &mut rng));
+        assert_eq!(honest_sumcheck_ip.run(), Status::Accepted);
+    }
+
+    #[test]
+    fn test_sumcheck_single_variable() {
+        let evals = vec![Fr::from(3), Fr::from(4)];
+        let mut ip = Driver::<SumCheck<Fr>>::new(evals.clone());
+        assert_eq!(ip.run(), Status::Accepted);
+
+        let mut ip = Driver::<SumCheck<Fr>, _>::with_adversary(evals, CorruptEntry::every_round(1));
+        ip.log_rejections = false;
+        assert_eq!(ip.run(), Status::Rejected);
+    }
+
+    #[test]
+    fn test_seeded_runs_are_reproducible() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let evals = random_evals::<Fr, _>(4, &mut rng);
+
+        let run = |seed| {
+            let mut ip =
+                Driver::<SumCheck<Fr>, _>::with_seed(evals.clone(), RandomMessage::default(), seed);
+            ip.log_rejections = false;
+            ip.run();
+            assert_eq!(ip.seed(), seed);
+            ip.into_transcript()
+        };
+        assert_eq!(run(7), run(7));
+    }
+
+    #[test]
+    fn test_sumcheck_book_example() {
+        let poly = OraclePolynomial::new(g_poly::<Fr>, vec![3, 1, 1]);
+        let (_, (sum, _)) = SumCheck::<Fr, _>::split_input(poly.clone());
+        assert_eq!(sum, Fr::from(12));
+
+        let mut ip = Driver::<SumCheck<Fr, _>>::new(poly.clone());
+        assert_eq!(ip.run(), Status::Accepted);
+        let lengths: Vec<usize> = ip.transcript().prover_messages().map(Vec::len).collect();
+        assert_eq!(lengths, vec![4, 2, 2]);
+
+        // The lie passes every round and can only be caught by the final oracle query, which
+        // misses it when r_1 = 1/2 is a root of the 2X - 1 added to g_1, so count over seeds
+        let rejected = (0..20)
+            .filter(|&seed| {
+                let mut ip = Driver::<SumCheck<Fr, _>, _>::with_seed(
+                    poly.clone(),
+                    LieInRound { round: 0 },
+                    seed,
+                );
+                ip.log_rejections = false;
+                let status = ip.run();
+                assert_eq!(ip.transcript().entries.len(), 6);
+                status == Status::Rejected
+            })
+            .count();
+        assert!(rejected > 0);
+    }
+
+    #[test]
+    fn test_sumcheck_sparse_polynomial() {
+        // x_1^2 x_2 + 3 x_2 x_4^4 + x_3 + 1
+        let poly = SparsePolynomial::from_coefficients_vec(
+            4,
+            vec![
+                (Fr::from(1), SparseTerm::new(vec![(0, 2), (1, 1)])),
+                (Fr::from(3), SparseTerm::new(vec![(1, 1), (3, 4)])),
+                (Fr::from(1), SparseTerm::new(vec![(2, 1)])),
+                (Fr::from(1), SparseTerm::new(vec![])),
+            ],
+        );
+        assert_eq!(poly.degrees(), vec![2, 1, 1, 4]);
+
+        let mut ip = Driver::<SumCheck<Fr, _>>::new(poly.clone());
+        assert_eq!(ip.run(), Status::Accepted);
+
+        let mut ip =
+            Driver::<SumCheck<Fr, _>, _>::with_adversary(poly, CorruptEntry::in_round(2, 0));
+        ip.log_rejections = false;
+        assert_eq!(ip.run(), Status::Rejected);
+    }
+
+    #[test]
+    fn test_sumcheck_product() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let v = 5;
+        let (a, b, c) = (
+            random_evals::<Fr, _>(v, &mut rng),
+            random_evals::<Fr, _>(v, &mut rng),
+            random_evals::<Fr, _>(v, &mut rng),
+        );
+        // a * b - c
+        let poly = ProductMLE::new(vec![a, b, c], |f| f[0] * f[1] - f[2], 2);
+
+        let mut ip = Driver::<SumCheck<Fr, _>>::with_seed(poly.clone(), Honest, 0);
+        assert_eq!(ip.run(), Status::Accepted);
+        let first = ip.transcript().prover_messages().next().unwrap();
+        assert_eq!(first.len(), 3);
+
+        let mut ip = Driver::<SumCheck<Fr, _>, _>::with_seed(poly, LieInRound { round: 2 }, 0);
+        ip.log_rejections = false;
+        assert_eq!(ip.run(), Status::Rejected);
+        assert_eq!(ip.transcript().entries.len(), 2 * v);
+    }
+
+    #[test]
+    fn test_no_variables() {
+        let mut ip = Driver::<SumCheck<Fr>>::new(vec![Fr::from(3)]);
+        assert_eq!(ip.run(), Status::Accepted);
+        assert!(ip.transcript().entries.is_empty());
+
+        // A claim of 4 for the constant 3
+        let mut ip = Driver::<SumCheck<Fr>>::from_parts(
+            SumCheckProver::new(vec![Fr::from(3)]),
+            SumCheckVerifier::initialize((Fr::from(4), vec![Fr::from(3)].into())),
+            Honest,
+            0,
+        );
+        ip.log_rejections = false;
+        assert_eq!(ip.run(), Status::Rejected);
+    }
+
+    #[test]
+    fn test_batched_sumcheck() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let v = 4;
+        let polys: Vec<Vec<Fr>> = (0..3).map(|_| random_evals(v, &mut rng)).collect();
+
+        let mut ip = Driver::<BatchedSumCheck<Fr>>::with_seed(polys.clone(), Honest, 0);
+        assert_eq!(ip.run(), Status::Accepted);
+        // One round for alpha, opened by an empty message, then a single sumcheck
+        assert_eq!(ip.transcript().entries.len(), 2 * (v + 1));
+        assert!(ip.transcript().prover_messages().next().unwrap().is_empty());
+
+        // Claiming a wrong sum for one of the polynomials
+        let (prover_input, (mut claims, polys)) = BatchedSumCheck::<Fr>::split_input(polys);
+        claims[1] += Fr::from(1);
+        let mut ip = Driver::<BatchedSumCheck<Fr>>::from_parts(
+            BatchedSumCheckProver::initialize(prover_input),
+            BatchedSumCheckVerifier::initialize((claims, polys.clone())),
+            Honest,
+            0,
+        );
+        ip.log_rejections = false;
+        assert_eq!(ip.run(), Status::Rejected);
+
+        let mut ip =
+            Driver::<BatchedSumCheck<Fr>, _>::with_seed(polys, CorruptEntry::in_round(2, 1), 0);
+        ip.log_rejections = false;
+        assert_eq!(ip.run(), Status::Rejected);
+    }
+
+    #[test]
+    fn test_verify_sumcheck() {
+        let poly = OraclePolynomial::new(g_poly::<Fr>, vec![3, 1, 1]);
+        let mut ip = Driver::<SumCheck<Fr, _>>::with_seed(poly.clone(), Honest, 0);
+        assert_eq!(ip.run(), Status::Accepted);
+        let (proof, challenges) = SumcheckProof::from_transcript(ip.transcript());
+
+        let degrees = poly.degrees();
+        let subclaim = verify_sumcheck(Fr::from(12), &degrees, 2, &proof, &challenges).unwrap();
+        assert_eq!(subclaim.point, challenges);
+        assert_eq!(poly.evaluate(&subclaim.point), subclaim.expected_value);
+
+        assert_eq!(
+            verify_sumcheck(Fr::from(11), &degrees, 2, &proof, &challenges),
+            Err(SumcheckError::InconsistentSum { round: 0 })
+        );
+        assert_eq!(
+            verify_sumcheck(Fr::from(12), &[2, 1, 1], 2, &proof, &challenges),
+            Err(SumcheckError::DegreeBound { round: 0 })
+        );
+        // x2 has degree 1, so its message has two evaluations
+        assert_eq!(
+            verify_sumcheck(Fr::from(12), &[3, 0, 1], 2, &proof, &challenges),
+            Err(SumcheckError::DegreeBound { round: 1 })
+        );
+        // The same messages summed over {0, 1, 2}
+        assert_eq!(
+            verify_sumcheck(Fr::from(12), &degrees, 3, &proof, &challenges),
+            Err(SumcheckError::InconsistentSum { round: 0 })
+        );
+        assert_eq!(
+            verify_sumcheck(Fr::from(12), &degrees, 2, &proof, &challenges[..2]),
+            Err(SumcheckError::WrongNumberOfRounds {
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_degree_beyond_field() {
+        // Degree 5 needs the 6 points 0, ..., 5, but 5 = 0 mod 5
+        let poly = OraclePolynomial::new(|x: &[Fr]| x[0] * x[1].pow([5]), vec![1, 5]);
+        let mut ip = Driver::<SumCheck<Fr, _>>::with_seed(poly, Honest, 0);
+        ip.log_rejections = false;
+        assert_eq!(ip.run(), Status::Rejected);
+        assert!(ip.transcript().entries.is_empty());
+
+        let proof = SumcheckProof {
+            round_messages: vec![vec![Fr::from(0); 2], vec![Fr::from(0); 5]],
+        };
+        let challenges = [Fr::from(1), Fr::from(2)];
+        assert_eq!(
+            verify_sumcheck(Fr::from(0), &[1, 5], 2, &proof, &challenges),
+            Err(SumcheckError::FieldTooSmall { round: 1 })
+        );
+        // Degree 4 still fits
+        assert!(verify_sumcheck(Fr::from(0), &[1, 4], 2, &proof, &challenges).is_ok());
+    }
+
+    #[test]
+    fn test_streaming_sumcheck() {
+        let v = 6;
+        let stream = StreamingMLE::new(v, move || {
+            let mut rng = ChaCha20Rng::seed_from_u64(7);
+            (0..1 << v).map(move |_| random_elem::<Fr, _>(&mut rng))
+        });
+        let evals = random_evals::<Fr, _>(v, &mut ChaCha20Rng::seed_from_u64(7));
+
+        let mut streaming = Driver::<SumCheck<Fr, _>>::with_seed(stream, Honest, 0);
+        assert_eq!(streaming.run(), Status::Accepted);
+        // Same messages as the prover holding the whole table
+        let mut dense = Driver::<SumCheck<Fr>>::with_seed(evals, Honest, 0);
+        dense.run();
+        assert_eq!(streaming.into_transcript(), dense.into_transcript());
+    }
+
+    #[test]
+    fn test_sparse_sumcheck() {
+        let v = 12;
+        let poly = SparseMLE::new(
+            v,
+            [(5, Fr::from(1)), (1000, Fr::from(3)), (4000, Fr::from(2))],
+        );
+        let mut ip = Driver::<SumCheck<Fr, _>>::with_seed(poly.clone(), Honest, 0);
+        assert_eq!(ip.run(), Status::Accepted);
+
+        let mut ip = Driver::<SumCheck<Fr, _>, _>::with_seed(poly, CorruptEntry::in_round(4, 0), 0);
+        ip.log_rejections = false;
+        assert_eq!(ip.run(), Status::Rejected);
+    }
+
+    #[test]
+    fn test_sumcheck_variable_orders() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let little = DenseMLE::new(random_evals::<Fr, _>(5, &mut rng));
+        let big = little.to_order(VariableOrder::BigEndian);
+        assert_ne!(big.evals(), little.evals());
+
+        let run = |mle: DenseMLE<Fr>| {
+            let mut ip = Driver::<SumCheck<Fr, _>>::with_seed(mle, Honest, 0);
+            assert_eq!(ip.run(), Status::Accepted);
+            ip.into_transcript()
+        };
+        // The same polynomial, so the same messages whatever order its table is in
+        assert_eq!(run(big), run(little));
+    }
 }
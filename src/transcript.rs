@@ -0,0 +1,361 @@
+use crate::ip::*;
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Valid, Validate,
+};
+use ark_std::io::{Read, Write};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Role {
+    Prover,
+    Verifier,
+}
+
+/// A single message sent during an IP run. Verifier messages also carry the seed of the coins the
+/// verifier used to produce them, so that the verifier can be re-run deterministically.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Message<P, V> {
+    Prover(P),
+    Verifier { message: V, seed: [u8; 32] },
+}
+
+impl<P, V> Message<P, V> {
+    pub fn role(&self) -> Role {
+        match self {
+            Message::Prover(_) => Role::Prover,
+            Message::Verifier { .. } => Role::Verifier,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct TranscriptEntry<P, V> {
+    pub round: usize,
+    pub message: Message<P, V>,
+}
+
+impl<P, V> TranscriptEntry<P, V> {
+    pub fn role(&self) -> Role {
+        self.message.role()
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ReplayError {
+    /// The entry at `index` was sent by the wrong party, or has the wrong round number
+    OutOfOrder { index: usize },
+    /// The verifier's recomputed message in `round` differs from the recorded one
+    Diverged { round: usize },
+    /// The transcript continues after the verifier has already decided
+    TrailingMessages { index: usize },
+}
+
+/// Uniform record of every message sent during an IP run, in order.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Transcript<P, V> {
+    pub entries: Vec<TranscriptEntry<P, V>>,
+}
+
+impl<P, V> Default for Transcript<P, V> {
+    fn default() -> Self {
+        Self { entries: vec![] }
+    }
+}
+
+impl<P, V> Transcript<P, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, round: usize, message: Message<P, V>) {
+        self.entries.push(TranscriptEntry { round, message });
+    }
+
+    // Round that the next message belongs to. Each round is a prover message followed by the
+    // verifier's reply.
+    pub fn current_round(&self) -> usize {
+        self.entries.len() / 2
+    }
+
+    pub fn prover_messages(&self) -> impl Iterator<Item = &P> {
+        self.entries
+            .iter()
+            .filter_map(|entry| match &entry.message {
+                Message::Prover(message) => Some(message),
+                Message::Verifier { .. } => None,
+            })
+    }
+
+    pub fn verifier_messages(&self) -> impl Iterator<Item = &V> {
+        self.entries
+            .iter()
+            .filter_map(|entry| match &entry.message {
+                Message::Prover(_) => None,
+                Message::Verifier { message, .. } => Some(message),
+            })
+    }
+
+    /// Re-runs a fresh verifier for `input` against the recorded prover messages, using the
+    /// recorded coins, and returns the status it reaches. A transcript that ends before the
+    /// verifier decides gives `Status::Running`.
+    pub fn replay<I>(&self, input: I::VerifierInput) -> Result<Status, ReplayError>
+    where
+        I: IP<ProverMessage = P, VerifierMessage = V>,
+        P: Clone,
+        V: PartialEq,
+    {
+        let mut verifier = I::Verifier::initialize(input);
+        for (index, entry) in self.entries.iter().enumerate() {
+            if verifier.get_status() != Status::Running {
+                return Err(ReplayError::TrailingMessages { index });
+            }
+            let expected_role = if index.is_multiple_of(2) {
+                Role::Prover
+            } else {
+                Role::Verifier
+            };
+            if entry.round != index / 2 || entry.role() != expected_role {
+                return Err(ReplayError::OutOfOrder { index });
+            }
+
+            match &entry.message {
+                Message::Prover(message) => verifier.receive_prover_message(message.clone()),
+                Message::Verifier { message, seed } => {
+                    let recomputed =
+                        verifier.run_verifier_logic(&mut ChaCha20Rng::from_seed(*seed));
+                    if recomputed != *message {
+                        return Err(ReplayError::Diverged { round: entry.round });
+                    }
+                }
+            }
+        }
+        Ok(verifier.get_status())
+    }
+}
+
+impl CanonicalSerialize for Role {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        (*self == Role::Verifier).serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        true.serialized_size(compress)
+    }
+}
+
+impl Valid for Role {
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for Role {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let is_verifier = bool::deserialize_with_mode(reader, compress, validate)?;
+        Ok(if is_verifier {
+            Role::Verifier
+        } else {
+            Role::Prover
+        })
+    }
+}
+
+// Entries are encoded as `round || role || message || seed`, with the seed only present for
+// verifier messages.
+impl<P: CanonicalSerialize, V: CanonicalSerialize> CanonicalSerialize for TranscriptEntry<P, V> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.round.serialize_with_mode(&mut writer, compress)?;
+        self.role().serialize_with_mode(&mut writer, compress)?;
+        match &self.message {
+            Message::Prover(message) => message.serialize_with_mode(&mut writer, compress),
+            Message::Verifier { message, seed } => {
+                message.serialize_with_mode(&mut writer, compress)?;
+                seed.serialize_with_mode(&mut writer, compress)
+            }
+        }
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        let message_size = match &self.message {
+            Message::Prover(message) => message.serialized_size(compress),
+            Message::Verifier { message, seed } => {
+                message.serialized_size(compress) + seed.serialized_size(compress)
+            }
+        };
+        self.round.serialized_size(compress) + self.role().serialized_size(compress) + message_size
+    }
+}
+
+impl<P: Valid, V: Valid> Valid for TranscriptEntry<P, V> {
+    fn check(&self) -> Result<(), SerializationError> {
+        match &self.message {
+            Message::Prover(message) => message.check(),
+            Message::Verifier { message, .. } => message.check(),
+        }
+    }
+}
+
+impl<P: CanonicalDeserialize, V: CanonicalDeserialize> CanonicalDeserialize
+    for TranscriptEntry<P, V>
+{
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let round = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        let message = match Role::deserialize_with_mode(&mut reader, compress, validate)? {
+            Role::Prover => {
+                Message::Prover(P::deserialize_with_mode(&mut reader, compress, validate)?)
+            }
+            Role::Verifier => Message::Verifier {
+                message: V::deserialize_with_mode(&mut reader, compress, validate)?,
+                seed: <[u8; 32]>::deserialize_with_mode(&mut reader, compress, validate)?,
+            },
+        };
+        Ok(Self { round, message })
+    }
+}
+
+impl<P: CanonicalSerialize, V: CanonicalSerialize> CanonicalSerialize for Transcript<P, V> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.entries.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.entries.serialized_size(compress)
+    }
+}
+
+impl<P: Valid, V: Valid> Valid for Transcript<P, V> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.entries.iter().try_for_each(|entry| entry.check())
+    }
+}
+
+impl<P: CanonicalDeserialize, V: CanonicalDeserialize> CanonicalDeserialize for Transcript<P, V> {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(Self {
+            entries: Vec::deserialize_with_mode(reader, compress, validate)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        adversary::{Adversary, CorruptEntry, Honest},
+        fields::Fr,
+        mle::random_evals,
+        oracle::SelfEvaluation,
+        sumcheck::SumCheck,
+    };
+
+    fn record<A: Adversary<SumCheck<Fr>>>(
+        evals: Vec<Fr>,
+        adversary: A,
+    ) -> (Status, Transcript<Vec<Fr>, Fr>) {
+        let mut ip = Driver::<SumCheck<Fr>, _>::with_seed(evals, adversary, 0);
+        ip.log_rejections = false;
+        let status = ip.run();
+        (status, ip.into_transcript())
+    }
+
+    fn statement(evals: &[Fr]) -> (Fr, SelfEvaluation<Fr, Vec<Fr>>) {
+        SumCheck::<Fr>::split_input(evals.to_vec()).1
+    }
+
+    #[test]
+    fn test_replay_sumcheck() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let v = 4;
+        let evals = random_evals::<Fr, _>(v, &mut rng);
+
+        let (status, transcript) = record(evals.clone(), Honest);
+        assert_eq!(status, Status::Accepted);
+        assert_eq!(transcript.entries.len(), 2 * v);
+        assert_eq!(transcript.entries[3].round, 1);
+        assert_eq!(transcript.entries[3].role(), Role::Verifier);
+        assert_eq!(transcript.prover_messages().count(), v);
+        assert_eq!(
+            transcript.replay::<SumCheck<Fr>>(statement(&evals)),
+            Ok(Status::Accepted)
+        );
+
+        let (status, transcript) = record(evals.clone(), CorruptEntry::every_round(0));
+        assert_eq!(status, Status::Rejected);
+        assert_eq!(
+            transcript.replay::<SumCheck<Fr>>(statement(&evals)),
+            Ok(Status::Rejected)
+        );
+    }
+
+    #[test]
+    fn test_transcript_serialization() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let evals = random_evals::<Fr, _>(3, &mut rng);
+        let (_, transcript) = record(evals.clone(), Honest);
+
+        let mut bytes = vec![];
+        transcript.serialize_compressed(&mut bytes).unwrap();
+        let decoded = Transcript::<Vec<Fr>, Fr>::deserialize_compressed(bytes.as_slice()).unwrap();
+        assert_eq!(decoded, transcript);
+        assert_eq!(
+            decoded.replay::<SumCheck<Fr>>(statement(&evals)),
+            Ok(Status::Accepted)
+        );
+    }
+
+    #[test]
+    fn test_replay_errors() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let evals = random_evals::<Fr, _>(3, &mut rng);
+        let (_, transcript) = record(evals.clone(), Honest);
+
+        let mut swapped = transcript.clone();
+        swapped.entries.swap(0, 1);
+        assert_eq!(
+            swapped.replay::<SumCheck<Fr>>(statement(&evals)),
+            Err(ReplayError::OutOfOrder { index: 0 })
+        );
+
+        // Recorded challenge no longer matches the coins it claims to come from
+        let mut tampered = transcript.clone();
+        if let Message::Verifier { message, .. } = &mut tampered.entries[1].message {
+            *message += Fr::from(1);
+        }
+        assert_eq!(
+            tampered.replay::<SumCheck<Fr>>(statement(&evals)),
+            Err(ReplayError::Diverged { round: 0 })
+        );
+
+        let mut extended = transcript.clone();
+        extended.entries.push(transcript.entries[0].clone());
+        assert_eq!(
+            extended.replay::<SumCheck<Fr>>(statement(&evals)),
+            Err(ReplayError::TrailingMessages { index: 6 })
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use crate::{
+    mle::{line_evals, DenseMLE, VariableOrder},
+    sumcheck::SumCheckPolynomial,
+};
+use ark_ff::Field;
+use ark_std::{
+    cfg_into_iter, cfg_iter,
+    ops::{Add, Mul, Neg, Sub},
+};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+// A sum of products of multilinear polynomials with coefficients,
+// sum_j c_j * prod_{i in products[j]} mles[i](x), built up with +, - and * from `DenseMLE`s, e.g.
+// a * b - c. Each MLE is stored once however many products use it, so (a + b) * a folds a's
+// table once per round rather than three times.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VirtualPolynomial<F: Field> {
+    num_vars: usize,
+    mles: Vec<DenseMLE<F>>,
+    // Coefficient, and indices into `mles` of the factors. No factors is a constant term.
+    products: Vec<(F, Vec<usize>)>,
+}
+
+impl<F: Field> VirtualPolynomial<F> {
+    pub fn constant(num_vars: usize, c: F) -> Self {
+        Self {
+            num_vars,
+            mles: vec![],
+            products: vec![(c, vec![])],
+        }
+    }
+
+    pub fn mles(&self) -> &[DenseMLE<F>] {
+        &self.mles
+    }
+
+    pub fn products(&self) -> &[(F, Vec<usize>)] {
+        &self.products
+    }
+
+    // The most factors in any product, which bounds the degree in each variable
+    pub fn degree(&self) -> usize {
+        self.products
+            .iter()
+            .map(|(_, factors)| factors.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    // Combines values of the MLEs, wherever they come from
+    fn combine(&self, values: &[F]) -> F {
+        self.products
+            .iter()
+            .map(|(c, factors)| *c * factors.iter().map(|&i| values[i]).product::<F>())
+            .sum()
+    }
+
+    pub fn evaluate(&self, point: &[F]) -> F {
+        let values: Vec<F> = self.mles.iter().map(|mle| mle.evaluate(point)).collect();
+        self.combine(&values)
+    }
+
+    // Value at the hypercube point with bit i of `index` giving x_{i+1}, whatever order the MLEs
+    // were given in
+    pub fn evaluate_at_index(&self, index: usize) -> F {
+        let values: Vec<F> = self.mles.iter().map(|mle| mle.evals()[index]).collect();
+        self.combine(&values)
+    }
+
+    // Adds the MLEs that `self` does not have yet, and returns the index each one ends up at
+    fn merge_mles(&mut self, mles: Vec<DenseMLE<F>>) -> Vec<usize> {
+        mles.into_iter()
+            .map(
+                |mle| match self.mles.iter().position(|existing| *existing == mle) {
+                    Some(i) => i,
+                    None => {
+                        self.mles.push(mle);
+                        self.mles.len() - 1
+                    }
+                },
+            )
+            .collect()
+    }
+
+    // Puts the products of `other` after those of `self`, with their factors renumbered
+    fn append(mut self, other: Self) -> Self {
+        assert_eq!(self.num_vars, other.num_vars);
+        let indices = self.merge_mles(other.mles);
+        self.products.extend(
+            other
+                .products
+                .into_iter()
+                .map(|(c, factors)| (c, factors.into_iter().map(|i| indices[i]).collect())),
+        );
+        self
+    }
+}
+
+// Stores the MLE in little-endian order, which hypercube indices refer to
+impl<F: Field> From<DenseMLE<F>> for VirtualPolynomial<F> {
+    fn from(mle: DenseMLE<F>) -> Self {
+        let mle = mle.to_order(VariableOrder::LittleEndian);
+        Self {
+            num_vars: mle.num_vars(),
+            mles: vec![mle],
+            products: vec![(F::one(), vec![0])],
+        }
+    }
+}
+
+impl<F: Field> Add for VirtualPolynomial<F> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.append(other)
+    }
+}
+
+impl<F: Field> Neg for VirtualPolynomial<F> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self * -F::one()
+    }
+}
+
+impl<F: Field> Sub for VirtualPolynomial<F> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl<F: Field> Mul<F> for VirtualPolynomial<F> {
+    type Output = Self;
+
+    fn mul(mut self, c: F) -> Self {
+        for (coeff, _) in &mut self.products {
+            *coeff *= c;
+        }
+        self
+    }
+}
+
+// Distributes, so the result has one product for each pair of products
+impl<F: Field> Mul for VirtualPolynomial<F> {
+    type Output = Self;
+
+    fn mul(mut self, other: Self) -> Self {
+        assert_eq!(self.num_vars, other.num_vars);
+        let lhs_products = std::mem::take(&mut self.products);
+        let indices = self.merge_mles(other.mles);
+        self.products = lhs_products
+            .iter()
+            .flat_map(|(c_l, factors_l)| {
+                other.products.iter().map(|(c_r, factors_r)| {
+                    let factors = factors_l
+                        .iter()
+                        .copied()
+                        .chain(factors_r.iter().map(|i| indices[*i]))
+                        .collect();
+                    (*c_l * c_r, factors)
+                })
+            })
+            .collect();
+        self
+    }
+}
+
+impl<F: Field> SumCheckPolynomial<F> for VirtualPolynomial<F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn degrees(&self) -> Vec<usize> {
+        vec![self.degree(); self.num_vars]
+    }
+
+    fn evaluate(&self, point: &[F]) -> F {
+        VirtualPolynomial::evaluate(self, point)
+    }
+
+    fn fix_first_variable(&self, r: F) -> Self {
+        Self {
+            num_vars: self.num_vars - 1,
+            mles: cfg_iter!(self.mles)
+                .map(|mle| mle.fix_variable(r))
+                .collect(),
+            products: self.products.clone(),
+        }
+    }
+
+    // The MLEs are all little-endian, so their tables can be walked as for `ProductMLE`
+    fn first_variable_evals(&self, num_points: usize) -> Vec<F> {
+        let tables: Vec<&[F]> = self.mles.iter().map(|mle| mle.evals()).collect();
+        line_evals(&tables, self.num_vars, num_points, |values| {
+            self.combine(values)
+        })
+    }
+
+    fn hypercube_sum(&self) -> F {
+        cfg_into_iter!(0..1usize << self.num_vars)
+            .map(|index| self.evaluate_at_index(index))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        adversary::Honest,
+        fields::{random_vec, Fr},
+        ip::*,
+        mle::random_evals,
+        sumcheck::SumCheck,
+    };
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    fn random_mle(v: usize, rng: &mut ChaCha20Rng) -> VirtualPolynomial<Fr> {
+        DenseMLE::new(random_evals(v, rng)).into()
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let v = 3;
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let (a, b, c) = (
+            random_mle(v, &mut rng),
+            random_mle(v, &mut rng),
+            random_mle(v, &mut rng),
+        );
+        let poly = (a.clone() + b.clone()) * c.clone() * Fr::from(3) - a.clone() * b.clone();
+        assert_eq!(poly.degree(), 2);
+        assert_eq!(poly.products().len(), 3);
+        assert_eq!(poly.mles().len(), 3);
+
+        let point = random_vec::<Fr, _>(v, &mut rng);
+        let (a_r, b_r, c_r) = (a.evaluate(&point), b.evaluate(&point), c.evaluate(&point));
+        assert_eq!(
+            poly.evaluate(&point),
+            (a_r + b_r) * c_r * Fr::from(3) - a_r * b_r
+        );
+
+        let index = 5;
+        let (a_i, b_i, c_i) = (
+            a.evaluate_at_index(index),
+            b.evaluate_at_index(index),
+            c.evaluate_at_index(index),
+        );
+        assert_eq!(
+            poly.evaluate_at_index(index),
+            (a_i + b_i) * c_i * Fr::from(3) - a_i * b_i
+        );
+        assert_eq!(
+            (poly.clone() + VirtualPolynomial::constant(v, Fr::from(1))).evaluate(&point),
+            poly.evaluate(&point) + Fr::from(1)
+        );
+        // 2^(v - 1) ones along each line
+        assert_eq!(
+            VirtualPolynomial::constant(v, Fr::from(1)).first_variable_evals(2),
+            vec![Fr::from(4); 2]
+        );
+    }
+
+    #[test]
+    fn test_virtual_sumcheck() {
+        let v = 5;
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let (a, b, c) = (
+            random_mle(v, &mut rng),
+            random_mle(v, &mut rng),
+            random_mle(v, &mut rng),
+        );
+        let poly = a.clone() * b * c - a;
+        assert_eq!(poly.degrees(), vec![3; v]);
+        assert_eq!(poly.mles().len(), 3);
+
+        let fixed = poly.fix_first_variable(Fr::from(2));
+        let point = random_vec::<Fr, _>(v - 1, &mut rng);
+        assert_eq!(
+            fixed.evaluate(&point),
+            poly.evaluate(&[&[Fr::from(2)], point.as_slice()].concat())
+        );
+
+        let mut ip = Driver::<SumCheck<Fr, _>>::with_seed(poly, Honest, 0);
+        assert_eq!(ip.run(), Status::Accepted);
+        let first = ip.transcript().prover_messages().next().unwrap();
+        assert_eq!(first.len(), 4);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{
+    fields::{field_size, random_vec},
+    ip::*,
+    mle::{eq_eval, ProductMLE},
+    polynomials::OraclePolynomial,
+    soundness::SoundnessBound,
+    sumcheck::{SumCheckPolynomial, SumCheckProver, SumCheckVerifier},
+};
+use ark_ff::FftField;
+use ark_std::marker::PhantomData;
+use rand::Rng;
+
+// IP proving that a polynomial vanishes on the whole boolean hypercube. The verifier picks a
+// random point r, and the two run sumcheck on eq(r, x) * f(x), whose sum over the hypercube is the
+// multilinear extension of f's hypercube values evaluated at r. That is zero for every r only if
+// f is zero everywhere on the hypercube.
+pub struct ZeroCheck<F: FftField>(PhantomData<F>);
+
+#[derive(Clone)]
+pub struct ZeroCheckProver<F: FftField> {
+    poly: ProductMLE<F>,
+    // Started once r is known
+    sumcheck: Option<SumCheckProver<F, ProductMLE<F>>>,
+}
+
+#[derive(Clone)]
+pub struct ZeroCheckVerifier<F: FftField> {
+    poly: ProductMLE<F>,
+    // The final check queries eq(r, x) directly, and f through one query per factor
+    sumcheck: Option<SumCheckVerifier<F, OraclePolynomial<F>>>,
+}
+
+impl<F: FftField> IP for ZeroCheck<F> {
+    // Empty in round 0, then sumcheck messages
+    type ProverMessage = Vec<F>;
+    // r in round 0, then each sumcheck challenge on its own
+    type VerifierMessage = Vec<F>;
+
+    type Input = ProductMLE<F>;
+    type ProverInput = ProductMLE<F>;
+    type VerifierInput = ProductMLE<F>;
+    type Prover = ZeroCheckProver<F>;
+    type Verifier = ZeroCheckVerifier<F>;
+
+    fn split_input(input: Self::Input) -> (Self::ProverInput, Self::VerifierInput) {
+        (input.clone(), input)
+    }
+}
+
+// v / |F| for r to be a root of the multilinear extension of f's hypercube values, plus
+// sumcheck's error on eq * f, which has degree d + 1 in each variable
+impl<F: FftField> SoundnessBound for ZeroCheck<F> {
+    fn soundness_error(input: &Self::Input) -> f64 {
+        let v = input.num_vars();
+        (v + v * (input.degree() + 1)) as f64 / field_size::<F>()
+    }
+}
+
+impl<F: FftField> Prover<ZeroCheck<F>> for ZeroCheckProver<F> {
+    fn initialize(input: ProductMLE<F>) -> Self {
+        Self {
+            poly: input,
+            sumcheck: None,
+        }
+    }
+
+    fn receive_verifier_message(&mut self, message: Vec<F>) {
+        match &mut self.sumcheck {
+            Some(sumcheck) => sumcheck.receive_challenge(message[0]),
+            None => self.sumcheck = Some(SumCheckProver::new(self.poly.times_eq(&message))),
+        }
+    }
+
+    fn run_prover_logic(&mut self) -> Vec<F> {
+        match &mut self.sumcheck {
+            Some(sumcheck) => sumcheck.round_message(),
+            None => vec![],
+        }
+    }
+}
+
+impl<F: FftField> Verifier<ZeroCheck<F>> for ZeroCheckVerifier<F> {
+    fn initialize(input: ProductMLE<F>) -> Self {
+        Self {
+            poly: input,
+            sumcheck: None,
+        }
+    }
+
+    fn receive_prover_message(&mut self, message: Vec<F>) {
+        // Nothing to check in the prover's empty opening message
+        if let Some(sumcheck) = &mut self.sumcheck {
+            sumcheck.receive_prover_message(message);
+        }
+    }
+
+    fn run_verifier_logic<R: Rng>(&mut self, rng: &mut R) -> Vec<F> {
+        if let Some(sumcheck) = &mut self.sumcheck {
+            return vec![sumcheck.run_verifier_logic(rng)];
+        }
+
+        let r = random_vec(self.poly.num_vars(), rng);
+        let f = self.poly.clone();
+        let eq_r = r.clone();
+        let eq_f = OraclePolynomial::new(
+            move |x: &[F]| eq_eval(&eq_r, x) * f.evaluate(x),
+            vec![self.poly.degree() + 1; self.poly.num_vars()],
+        );
+        self.sumcheck = Some(SumCheckVerifier::initialize((F::zero(), eq_f.into())));
+        r
+    }
+
+    fn get_status(&self) -> Status {
+        match &self.sumcheck {
+            Some(sumcheck) => sumcheck.get_status(),
+            None => Status::Running,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        adversary::Honest,
+        fields::Fr,
+        mle::{index_to_vars, random_evals},
+    };
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    // a * b - c, which vanishes on the hypercube when c is the entrywise product of a and b
+    fn constraint(v: usize, rng: &mut ChaCha20Rng) -> (Vec<Fr>, Vec<Fr>, Vec<Fr>) {
+        let a = random_evals::<Fr, _>(v, rng);
+        let b = random_evals::<Fr, _>(v, rng);
+        let c = a.iter().zip(&b).map(|(a, b)| *a * b).collect();
+        (a, b, c)
+    }
+
+    #[test]
+    fn test_zerocheck() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let v = 4;
+        let (a, b, c) = constraint(v, &mut rng);
+        let poly = ProductMLE::new(vec![a, b, c], |f| f[0] * f[1] - f[2], 2);
+        // Vanishes at every point of the hypercube
+        for index in 0..1 << v {
+            assert_eq!(poly.evaluate(&index_to_vars(index, v)), Fr::from(0));
+        }
+
+        let mut ip = Driver::<ZeroCheck<Fr>>::with_seed(poly, Honest, 0);
+        assert_eq!(ip.run(), Status::Accepted);
+        assert_eq!(ip.transcript().entries.len(), 2 * (v + 1));
+        let first = ip.transcript().prover_messages().nth(1).unwrap();
+        assert_eq!(first.len(), 4);
+    }
+
+    #[test]
+    fn test_zerocheck_nonzero() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let v = 4;
+        let (a, b, mut c) = constraint(v, &mut rng);
+        // Nonzero at two points, with values that cancel in the sum over the hypercube
+        c[3] += Fr::from(1);
+        c[5] -= Fr::from(1);
+        let poly = ProductMLE::new(vec![a, b, c], |f| f[0] * f[1] - f[2], 2);
+        // Sums to zero without vanishing, which a sumcheck for 0 would not catch
+        assert_eq!(poly.hypercube_sum(), Fr::from(0));
+
+        // The soundness error over a field of size 5 is large, so only some choices of r catch it
+        let rejected = (0..20)
+            .filter(|&seed| {
+                let mut ip = Driver::<ZeroCheck<Fr>>::with_seed(poly.clone(), Honest, seed);
+                ip.log_rejections = false;
+                ip.run() == Status::Rejected
+            })
+            .count();
+        assert!(rejected > 0);
+    }
+}